@@ -2,11 +2,14 @@ use anyhow::{anyhow, Result};
 use once_cell::sync::Lazy;
 use std::{
     borrow::Cow,
-    env,
+    env, fs,
     path::{Path, PathBuf},
 };
 
 fn main() -> Result<()> {
+    #[cfg(feature = "doc-only")]
+    println!("cargo:rustc-env=LIBRSB_LONG_INDICES=0");
+
     #[cfg(not(feature = "doc-only"))]
     {
         #[cfg(target_os = "linux")]
@@ -14,6 +17,7 @@ fn main() -> Result<()> {
             #[cfg(feature = "codegen")]
             codegen::codegen()?;
 
+            long_indices()?;
             link()?;
         }
 
@@ -83,6 +87,29 @@ mod codegen {
     }
 }
 
+/// Tell the crate whether librsb was configured with 64-bit indices.
+#[allow(dead_code)]
+fn long_indices() -> Result<()> {
+    let include_dir = probe_include_dir().ok_or_else(|| anyhow!("unable to find include dir"))?;
+    let long_indices = probe_long_indices(&include_dir);
+    println!(
+        "cargo:rustc-env=LIBRSB_LONG_INDICES={}",
+        if long_indices { 1 } else { 0 }
+    );
+
+    // The bundled bindings declare 32-bit indices and would not match the
+    // library ABI.
+    #[cfg(not(feature = "codegen"))]
+    if long_indices {
+        anyhow::bail!(
+            "librsb uses 64-bit indices, but the bundled bindings use 32-bit ones. \
+             Enable the codegen feature to regenerate them."
+        );
+    }
+
+    Ok(())
+}
+
 #[allow(dead_code)]
 fn link() -> Result<()> {
     let library_dir = probe_library_dir().ok_or_else(|| anyhow!("unable to find library dir"))?;
@@ -137,4 +164,31 @@ mod probe {
 
         path.or_else(|| Some(Cow::Borrowed(Path::new("/usr/lib"))))
     }
+
+    /// Check whether `RSB_WANT_LONG_IDX_TYPE` is enabled in the installed headers.
+    pub fn probe_long_indices(include_dir: &Path) -> bool {
+        ["rsb-config.h", "rsb_types.h"].iter().any(|file| {
+            let path = include_dir.join(file);
+            println!("cargo:rerun-if-changed={}", path.display());
+            let text = match fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(_) => return false,
+            };
+
+            text.lines().any(|line| {
+                let mut tokens = line.split_whitespace();
+                if tokens.next() != Some("#define")
+                    || tokens.next() != Some("RSB_WANT_LONG_IDX_TYPE")
+                {
+                    return false;
+                }
+
+                // An empty value counts as enabled.
+                match tokens.next() {
+                    None => true,
+                    Some(value) => value.trim_matches(|c| c == '(' || c == ')') != "0",
+                }
+            })
+        })
+    }
 }
@@ -41,6 +41,9 @@
 //! - `LIBRSB_LIBRARY`
 //!
 //!   The directory to search for library files. It defaults to `$LIBRSB_PATH/lib` if not set.
+//!
+//! The `RSB_WANT_LONG_IDX_TYPE` setting in `rsb-config.h` and `rsb_types.h` under the
+//! include directory is exported as [LONG_INDICES].
 
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
@@ -48,3 +51,6 @@
 
 mod bindings;
 pub use bindings::*;
+
+/// Whether librsb was configured with 64-bit indices, as found in its headers at build time.
+pub const LONG_INDICES: bool = matches!(env!("LIBRSB_LONG_INDICES").as_bytes(), b"1");
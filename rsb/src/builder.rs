@@ -0,0 +1,190 @@
+use crate::{
+    common::*,
+    duplicates::Duplicates,
+    error::{check, ensure, Error, Result, RSB_ERR_NO_ERROR},
+    flag::Flags,
+    index_interface::{to_coo_idx, to_nnz_idx, IndexBase, IndexType},
+    mtx::Mtx,
+    numerical_type::NumericalType,
+    options::MtxOptions,
+};
+
+/// Assembles a [Mtx] from entries inserted one at a time or in batches.
+///
+/// librsb collects the entries in its own buffers, so the caller does not need
+/// to keep all the triplets in memory. Call [finish](MtxBuilder::finish) to
+/// build the matrix.
+pub struct MtxBuilder<T>
+where
+    T: NumericalType,
+{
+    ptr: Option<NonNull<sys::rsb_mtx_t>>,
+    nrows: sys::rsb_coo_idx_t,
+    ncols: sys::rsb_coo_idx_t,
+    duplicates: Duplicates,
+    index_base: IndexBase,
+    num_pushed: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> MtxBuilder<T>
+where
+    T: NumericalType,
+{
+    /// Start assembling a `nrows` by `ncols` matrix.
+    ///
+    /// The `nnz_hint` is the expected number of entries. Entries at the same
+    /// position are summed if the options contain [Flags::DUPLICATES_SUM], and
+    /// the last one is kept otherwise. The block sizes in the options are not
+    /// used by librsb here.
+    pub fn new<O>(nrows: usize, ncols: usize, nnz_hint: usize, options: O) -> Result<Self>
+    where
+        O: Into<MtxOptions>,
+    {
+        crate::init::init();
+
+        let nrows = to_coo_idx(nrows)?;
+        let ncols = to_coo_idx(ncols)?;
+        let flags = options.into().to_flags();
+        let duplicates = if flags.contains(Flags::DUPLICATES_SUM) {
+            Duplicates::Sum
+        } else {
+            Duplicates::Overwrite
+        };
+        let index_base = IndexBase::from_flags(flags);
+
+        let ptr = unsafe {
+            let mut errval = RSB_ERR_NO_ERROR;
+            let ptr = sys::rsb_mtx_alloc_from_coo_begin(
+                to_nnz_idx(nnz_hint)?,
+                T::TYPE_CODE,
+                nrows,
+                ncols,
+                flags.code(),
+                &mut errval as *mut _,
+            );
+            check(errval, "builder_begin")?;
+            ptr
+        };
+
+        Ok(Self {
+            ptr: Some(NonNull::new(ptr).unwrap()),
+            nrows,
+            ncols,
+            duplicates,
+            index_base,
+            num_pushed: 0,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Get how entries at the same position are combined.
+    pub fn duplicates(&self) -> Duplicates {
+        self.duplicates
+    }
+
+    /// Get the base of the inserted row and column indices.
+    pub fn index_base(&self) -> IndexBase {
+        self.index_base
+    }
+
+    /// Get the number of entries inserted so far, counting duplicates.
+    pub fn num_pushed(&self) -> usize {
+        self.num_pushed
+    }
+
+    pub fn push(&mut self, row: usize, col: usize, val: T) -> Result<()> {
+        self.push_batch(&[row], &[col], &[val])
+    }
+
+    pub fn push_batch<I>(&mut self, rows: &[I], cols: &[I], vals: &[T]) -> Result<()>
+    where
+        I: IndexType,
+    {
+        let nnz = rows.len();
+        ensure!(
+            cols.len() == nnz && vals.len() == nnz,
+            "the length of slices must be equal"
+        );
+        let rows = I::to_coo_indices(rows)?;
+        let cols = I::to_coo_indices(cols)?;
+        let offset = self.index_base.offset();
+        check_indices(&rows, offset..offset + self.nrows, "row")?;
+        check_indices(&cols, offset..offset + self.ncols, "column")?;
+
+        unsafe {
+            let err = sys::rsb_mtx_set_vals(
+                self.ptr.unwrap().as_ptr(),
+                vals.as_ptr() as *const c_void,
+                rows.as_ptr(),
+                cols.as_ptr(),
+                to_nnz_idx(nnz)?,
+                self.duplicates.code() | self.index_base.code(),
+            );
+            check(err, "builder_push")?;
+        }
+
+        self.num_pushed += nnz;
+        Ok(())
+    }
+
+    /// Assemble the inserted entries into a matrix.
+    pub fn finish(mut self) -> Result<Mtx<T>> {
+        let mut ptr = self.ptr.take().unwrap().as_ptr();
+
+        unsafe {
+            let err = sys::rsb_mtx_alloc_from_coo_end(&mut ptr as *mut _);
+            if err != RSB_ERR_NO_ERROR {
+                // Release the matrix if librsb has not done so.
+                if !ptr.is_null() {
+                    drop(Mtx::<T>::from_raw(ptr));
+                }
+                check(err, "builder_finish")?;
+            }
+
+            Ok(Mtx::from_raw(ptr))
+        }
+    }
+}
+
+impl<T> fmt::Debug for MtxBuilder<T>
+where
+    T: NumericalType,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MtxBuilder")
+            .field("nrows", &self.nrows)
+            .field("ncols", &self.ncols)
+            .field("duplicates", &self.duplicates)
+            .field("index_base", &self.index_base)
+            .field("num_pushed", &self.num_pushed)
+            .finish()
+    }
+}
+
+impl<T> Drop for MtxBuilder<T>
+where
+    T: NumericalType,
+{
+    fn drop(&mut self) {
+        if let Some(mut ptr) = self.ptr.take() {
+            unsafe {
+                sys::rsb_mtx_free(ptr.as_mut());
+            }
+        }
+    }
+}
+
+fn check_indices(
+    indices: &[sys::rsb_coo_idx_t],
+    bound: Range<sys::rsb_coo_idx_t>,
+    name: &str,
+) -> Result<()> {
+    if let Some(index) = indices.iter().find(|index| !bound.contains(index)) {
+        return Err(Error::custom(format!(
+            "the {} index {} is out of bound {:?}",
+            name, index, bound
+        )));
+    }
+    Ok(())
+}
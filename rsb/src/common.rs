@@ -3,16 +3,17 @@ pub use librsb_sys as sys;
 pub use slice_of_array::prelude::*;
 pub use std::{
     borrow::Cow,
-    ffi::c_void,
+    ffi::{c_void, CStr},
     fmt,
     fmt::Display,
     marker::PhantomData,
-    ops::Deref,
+    mem,
+    ops::{Deref, Range, RangeInclusive},
     os::raw::{c_char, c_int, c_uint},
     path::Path,
     ptr,
     ptr::NonNull,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use unzip_n::unzip_n;
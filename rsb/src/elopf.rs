@@ -0,0 +1,57 @@
+use crate::{common::*, numerical_type::NumericalType};
+
+/// An element-wise update applied to the nonzeros of a matrix.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ElementOp<'a, T>
+where
+    T: NumericalType,
+{
+    /// Multiply every nonzero by a scalar.
+    Mul(T),
+    /// Divide every nonzero by a scalar.
+    Div(T),
+    /// Raise every nonzero to a power.
+    Pow(T),
+    /// Negate every nonzero.
+    Neg,
+    /// Multiply the i-th row by the i-th element.
+    ScaleRows(&'a [T]),
+    /// Multiply the j-th column by the j-th element.
+    ScaleCols(&'a [T]),
+    /// Multiply the i-th row by the i-th real element.
+    ScaleRowsReal(&'a [T::Real]),
+    /// Multiply the j-th column by the j-th real element.
+    ScaleColsReal(&'a [T::Real]),
+}
+
+impl<'a, T> ElementOp<'a, T>
+where
+    T: NumericalType,
+{
+    pub fn code(&self) -> sys::rsb_elopf_t {
+        match self {
+            Self::Mul(_) => sys::rsb_elopf_t::RSB_ELOPF_MUL,
+            Self::Div(_) => sys::rsb_elopf_t::RSB_ELOPF_DIV,
+            Self::Pow(_) => sys::rsb_elopf_t::RSB_ELOPF_POW,
+            Self::Neg => sys::rsb_elopf_t::RSB_ELOPF_NEG,
+            Self::ScaleRows(_) => sys::rsb_elopf_t::RSB_ELOPF_SCALE_ROWS,
+            Self::ScaleCols(_) => sys::rsb_elopf_t::RSB_ELOPF_SCALE_COLS,
+            Self::ScaleRowsReal(_) => sys::rsb_elopf_t::RSB_ELOPF_SCALE_ROWS_REAL,
+            Self::ScaleColsReal(_) => sys::rsb_elopf_t::RSB_ELOPF_SCALE_COLS_REAL,
+        }
+    }
+
+    /// Get the pointer to the operand, which is null for [ElementOp::Neg].
+    pub(crate) fn as_ptr(&self) -> *const c_void {
+        match self {
+            Self::Mul(value) | Self::Div(value) | Self::Pow(value) => {
+                value as *const T as *const c_void
+            }
+            Self::Neg => ptr::null(),
+            Self::ScaleRows(slice) | Self::ScaleCols(slice) => slice.as_ptr() as *const c_void,
+            Self::ScaleRowsReal(slice) | Self::ScaleColsReal(slice) => {
+                slice.as_ptr() as *const c_void
+            }
+        }
+    }
+}
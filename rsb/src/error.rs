@@ -1,12 +1,31 @@
 use crate::common::*;
 
 pub const RSB_ERR_NO_ERROR: sys::rsb_err_t = sys::rsb_err_t(0);
+pub const RSB_ERR_GENERIC_ERROR: sys::rsb_err_t = sys::rsb_err_t(-0x001);
+pub const RSB_ERR_UNSUPPORTED_OPERATION: sys::rsb_err_t = sys::rsb_err_t(-0x002);
+pub const RSB_ERR_UNSUPPORTED_TYPE: sys::rsb_err_t = sys::rsb_err_t(-0x004);
+pub const RSB_ERR_UNSUPPORTED_FORMAT: sys::rsb_err_t = sys::rsb_err_t(-0x008);
+pub const RSB_ERR_INTERNAL_ERROR: sys::rsb_err_t = sys::rsb_err_t(-0x010);
+pub const RSB_ERR_BADARGS: sys::rsb_err_t = sys::rsb_err_t(-0x020);
+pub const RSB_ERR_ENOMEM: sys::rsb_err_t = sys::rsb_err_t(-0x040);
+pub const RSB_ERR_UNIMPLEMENTED_YET: sys::rsb_err_t = sys::rsb_err_t(-0x100);
+pub const RSB_ERR_LIMITS: sys::rsb_err_t = sys::rsb_err_t(-0x200);
+pub const RSB_ERR_UNSUPPORTED_FEATURE: sys::rsb_err_t = sys::rsb_err_t(-0x400);
+pub const RSB_ERR_NO_USER_CONFIGURATION: sys::rsb_err_t = sys::rsb_err_t(-0x800);
+pub const RSB_ERR_CORRUPT_INPUT_DATA: sys::rsb_err_t = sys::rsb_err_t(-0x1000);
+pub const RSB_ERR_FAILED_MEMHIER_DETECTION: sys::rsb_err_t = sys::rsb_err_t(-0x2000);
+pub const RSB_ERR_COULD_NOT_HONOUR_EXTERNALLY_ALLOCATION_FLAGS: sys::rsb_err_t =
+    sys::rsb_err_t(-0x4000);
+pub const RSB_ERR_NO_STREAM_OUTPUT_CONFIGURED_OUT: sys::rsb_err_t = sys::rsb_err_t(-0x8000);
+pub const RSB_ERR_INVALID_NUMERICAL_DATA: sys::rsb_err_t = sys::rsb_err_t(-0x10000);
+pub const RSB_ERR_MEMORY_LEAK: sys::rsb_err_t = sys::rsb_err_t(-0x20000);
 
-pub(crate) fn check(err: sys::rsb_err_t) -> Result<(), Error> {
+/// Check the error code returned by librsb within the named wrapper operation.
+pub(crate) fn check(err: sys::rsb_err_t, operation: &'static str) -> Result<(), Error> {
     if err == RSB_ERR_NO_ERROR {
         Ok(())
     } else {
-        Err(Error::Rsb(err))
+        Err(Error::Rsb(RsbError::new(err, operation)))
     }
 }
 
@@ -14,7 +33,12 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Error {
-    Rsb(sys::rsb_err_t),
+    Rsb(RsbError),
+    /// An index or size does not fit in the librsb index type.
+    IndexOverflow {
+        value: usize,
+        max: usize,
+    },
     Custom(Cow<'static, str>),
 }
 
@@ -25,12 +49,25 @@ impl Error {
     {
         Self::Custom(desc.into())
     }
+
+    /// Record the shape of an operand of the failed operation.
+    pub(crate) fn with_shape(mut self, name: &'static str, hw: [usize; 2]) -> Self {
+        if let Self::Rsb(err) = &mut self {
+            err.shapes.push((name, hw));
+        }
+        self
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Rsb(code) => writeln!(f, "rsb error: code = {}", code.0),
+            Self::Rsb(err) => writeln!(f, "{}", err),
+            Self::IndexOverflow { value, max } => writeln!(
+                f,
+                "the index or size {} exceeds the maximum {} supported by librsb",
+                value, max
+            ),
             Self::Custom(desc) => writeln!(f, "{}", desc),
         }
     }
@@ -38,6 +75,127 @@ impl Display for Error {
 
 impl std::error::Error for Error {}
 
+impl From<RsbError> for Error {
+    fn from(err: RsbError) -> Self {
+        Self::Rsb(err)
+    }
+}
+
+/// An error reported by librsb.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RsbError {
+    /// The decoded error code.
+    pub kind: ErrorKind,
+    /// The raw error code.
+    pub code: sys::rsb_err_t,
+    /// The description given by `rsb_strerror_r()`.
+    pub message: String,
+    /// The wrapper operation that failed, such as `spmv` or `load`.
+    pub operation: &'static str,
+    /// The shapes of the operands involved in the operation.
+    pub shapes: Vec<(&'static str, [usize; 2])>,
+}
+
+impl RsbError {
+    pub fn new(code: sys::rsb_err_t, operation: &'static str) -> Self {
+        Self {
+            kind: ErrorKind::from_code(code),
+            code,
+            message: strerror(code),
+            operation,
+            shapes: vec![],
+        }
+    }
+}
+
+impl Display for RsbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rsb error in {}", self.operation)?;
+
+        if !self.shapes.is_empty() {
+            let shapes = self
+                .shapes
+                .iter()
+                .map(|(name, [nr, nc])| format!("{} {}x{}", name, nr, nc))
+                .join(", ");
+            write!(f, " ({})", shapes)?;
+        }
+
+        write!(f, ": {:?} (code = {})", self.kind, self.code.0)?;
+
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for RsbError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    GenericError,
+    UnsupportedOperation,
+    UnsupportedType,
+    UnsupportedFormat,
+    InternalError,
+    BadArgs,
+    NoMemory,
+    UnimplementedYet,
+    Limits,
+    UnsupportedFeature,
+    NoUserConfiguration,
+    CorruptInputData,
+    FailedMemoryHierarchyDetection,
+    CouldNotHonourExternallyAllocationFlags,
+    NoStreamOutputConfiguredOut,
+    InvalidNumericalData,
+    MemoryLeak,
+    Unknown,
+}
+
+impl ErrorKind {
+    pub fn from_code(code: sys::rsb_err_t) -> Self {
+        match code {
+            RSB_ERR_GENERIC_ERROR => Self::GenericError,
+            RSB_ERR_UNSUPPORTED_OPERATION => Self::UnsupportedOperation,
+            RSB_ERR_UNSUPPORTED_TYPE => Self::UnsupportedType,
+            RSB_ERR_UNSUPPORTED_FORMAT => Self::UnsupportedFormat,
+            RSB_ERR_INTERNAL_ERROR => Self::InternalError,
+            RSB_ERR_BADARGS => Self::BadArgs,
+            RSB_ERR_ENOMEM => Self::NoMemory,
+            RSB_ERR_UNIMPLEMENTED_YET => Self::UnimplementedYet,
+            RSB_ERR_LIMITS => Self::Limits,
+            RSB_ERR_UNSUPPORTED_FEATURE => Self::UnsupportedFeature,
+            RSB_ERR_NO_USER_CONFIGURATION => Self::NoUserConfiguration,
+            RSB_ERR_CORRUPT_INPUT_DATA => Self::CorruptInputData,
+            RSB_ERR_FAILED_MEMHIER_DETECTION => Self::FailedMemoryHierarchyDetection,
+            RSB_ERR_COULD_NOT_HONOUR_EXTERNALLY_ALLOCATION_FLAGS => {
+                Self::CouldNotHonourExternallyAllocationFlags
+            }
+            RSB_ERR_NO_STREAM_OUTPUT_CONFIGURED_OUT => Self::NoStreamOutputConfiguredOut,
+            RSB_ERR_INVALID_NUMERICAL_DATA => Self::InvalidNumericalData,
+            RSB_ERR_MEMORY_LEAK => Self::MemoryLeak,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Get the librsb description of an error code.
+fn strerror(code: sys::rsb_err_t) -> String {
+    let mut buf = vec![0 as c_char; 256];
+
+    let err = unsafe { sys::rsb_strerror_r(code, buf.as_mut_ptr(), buf.len() as sys::size_t) };
+    if err != RSB_ERR_NO_ERROR {
+        return String::new();
+    }
+
+    *buf.last_mut().unwrap() = 0;
+    let text = unsafe { CStr::from_ptr(buf.as_ptr()) };
+    text.to_string_lossy().trim().to_string()
+}
+
 macro_rules! ensure {
     ($cond:expr, $($args:tt)*) => {
         if ! $cond {
@@ -1 +1,121 @@
+use crate::{
+    common::*, discard_zero::DiscardZero, duplicates::Duplicates, index_interface::IndexBase,
+    major_order::MajorOrder, storage::StorageFormat, symmetry::Symmetry,
+};
+use std::ops::{BitOr, BitOrAssign};
 
+bitflags::bitflags! {
+    /// The `RSB_FLAG_*` flags controlling how librsb assembles and stores a matrix.
+    ///
+    /// The typed options [Symmetry], [Duplicates], [DiscardZero], [MajorOrder],
+    /// [IndexBase] and [StorageFormat] convert into `Flags` and can be combined with `|`.
+    #[derive(Default)]
+    pub struct Flags: sys::rsb_flags_t {
+        /// Indices are 1-based.
+        const FORTRAN_INDICES_INTERFACE = sys::RSB_FLAG_FORTRAN_INDICES_INTERFACE as sys::rsb_flags_t;
+        /// Use 16-bit indices in the leaves where possible.
+        const USE_HALFWORD_INDICES = sys::RSB_FLAG_USE_HALFWORD_INDICES as sys::rsb_flags_t;
+        /// Dense operands are in column major order.
+        const WANT_COLUMN_MAJOR_ORDER = sys::RSB_FLAG_WANT_COLUMN_MAJOR_ORDER as sys::rsb_flags_t;
+        /// The input is already sorted by row, then by column.
+        const SORTED_INPUT = sys::RSB_FLAG_SORTED_INPUT as sys::rsb_flags_t;
+        const TRIANGULAR = sys::RSB_FLAG_TRIANGULAR as sys::rsb_flags_t;
+        const LOWER = sys::RSB_FLAG_LOWER as sys::rsb_flags_t;
+        const UPPER = sys::RSB_FLAG_UPPER as sys::rsb_flags_t;
+        /// The diagonal is implicitly one and not stored.
+        const UNIT_DIAG_IMPLICIT = sys::RSB_FLAG_UNIT_DIAG_IMPLICIT as sys::rsb_flags_t;
+        /// Allow coordinate leaves.
+        const WANT_COO_STORAGE = sys::RSB_FLAG_WANT_COO_STORAGE as sys::rsb_flags_t;
+        /// Sum duplicated entries instead of keeping the last one.
+        const DUPLICATES_SUM = sys::RSB_FLAG_DUPLICATES_SUM as sys::rsb_flags_t;
+        /// Drop explicitly stored zeros.
+        const DISCARD_ZEROS = sys::RSB_FLAG_DISCARD_ZEROS as sys::rsb_flags_t;
+        /// Partition the matrix recursively into quadrants.
+        const QUAD_PARTITIONING = sys::RSB_FLAG_QUAD_PARTITIONING as sys::rsb_flags_t;
+        /// Allow compressed sparse row leaves.
+        const WANT_BCSS_STORAGE = sys::RSB_FLAG_WANT_BCSS_STORAGE as sys::rsb_flags_t;
+        const ASSEMBLED_IN_COO_ARRAYS = sys::RSB_FLAG_ASSEMBLED_IN_COO_ARRAYS as sys::rsb_flags_t;
+        /// Sort the input in place, saving memory during assembly.
+        const EXPERIMENTAL_IN_PLACE_PERMUTATION_SORT =
+            sys::RSB_FLAG_EXPERIMENTAL_IN_PLACE_PERMUTATION_SORT as sys::rsb_flags_t;
+        const SYMMETRIC = sys::RSB_FLAG_SYMMETRIC as sys::rsb_flags_t;
+        const HERMITIAN = sys::RSB_FLAG_HERMITIAN as sys::rsb_flags_t;
+        const RECURSIVE_MORE_LEAVES_THAN_THREADS =
+            sys::RSB_FLAG_RECURSIVE_MORE_LEAVES_THAN_THREADS as sys::rsb_flags_t;
+        const RECURSIVE_SUBDIVIDE_MORE_ON_DIAG =
+            sys::RSB_FLAG_RECURSIVE_SUBDIVIDE_MORE_ON_DIAG as sys::rsb_flags_t;
+        /// The matrix uses arrays allocated by the caller.
+        const EXTERNALLY_ALLOCATED_ARRAYS =
+            sys::RSB_FLAG_EXTERNALLY_ALLOCATED_ARRAYS as sys::rsb_flags_t;
+        const USE_CSR_RESERVED = sys::RSB_FLAG_USE_CSR_RESERVED as sys::rsb_flags_t;
+
+        const LOWER_SYMMETRIC = sys::RSB_FLAG_LOWER_SYMMETRIC as sys::rsb_flags_t;
+        const UPPER_SYMMETRIC = sys::RSB_FLAG_UPPER_SYMMETRIC as sys::rsb_flags_t;
+        const LOWER_HERMITIAN = sys::RSB_FLAG_LOWER_HERMITIAN as sys::rsb_flags_t;
+        const UPPER_HERMITIAN = sys::RSB_FLAG_UPPER_HERMITIAN as sys::rsb_flags_t;
+        const LOWER_TRIANGULAR = sys::RSB_FLAG_LOWER_TRIANGULAR as sys::rsb_flags_t;
+        const UPPER_TRIANGULAR = sys::RSB_FLAG_UPPER_TRIANGULAR as sys::rsb_flags_t;
+        const DIAGONAL = sys::RSB_FLAG_DIAGONAL as sys::rsb_flags_t;
+        const USE_HALFWORD_INDICES_COO = sys::RSB_FLAG_USE_HALFWORD_INDICES_COO as sys::rsb_flags_t;
+        const USE_HALFWORD_INDICES_CSR = sys::RSB_FLAG_USE_HALFWORD_INDICES_CSR as sys::rsb_flags_t;
+
+        /// The same as `RSB_FLAG_DEFAULT_STORAGE_FLAGS`.
+        const DEFAULT_STORAGE = Self::WANT_BCSS_STORAGE.bits | Self::WANT_COO_STORAGE.bits;
+        /// The same as `RSB_FLAG_DEFAULT_COO_MATRIX_FLAGS`.
+        const DEFAULT_COO_MATRIX = Self::WANT_COO_STORAGE.bits;
+        /// The same as `RSB_FLAG_DEFAULT_CSR_MATRIX_FLAGS`.
+        const DEFAULT_CSR_MATRIX = Self::WANT_BCSS_STORAGE.bits;
+        /// The same as `RSB_FLAG_DEFAULT_RSB_MATRIX_FLAGS`.
+        const DEFAULT_RSB_MATRIX = Self::DEFAULT_STORAGE.bits
+            | Self::QUAD_PARTITIONING.bits
+            | Self::USE_HALFWORD_INDICES.bits;
+    }
+}
+
+impl Flags {
+    pub fn code(&self) -> sys::rsb_flags_t {
+        self.bits()
+    }
+}
+
+macro_rules! impl_flag_option {
+    ($ty:ty) => {
+        impl From<$ty> for Flags {
+            fn from(option: $ty) -> Self {
+                Self::from_bits_truncate(option.code())
+            }
+        }
+
+        impl BitOr<$ty> for Flags {
+            type Output = Flags;
+
+            fn bitor(self, rhs: $ty) -> Flags {
+                self | Flags::from(rhs)
+            }
+        }
+
+        impl BitOrAssign<$ty> for Flags {
+            fn bitor_assign(&mut self, rhs: $ty) {
+                *self |= Flags::from(rhs);
+            }
+        }
+
+        impl<F> BitOr<F> for $ty
+        where
+            F: Into<Flags>,
+        {
+            type Output = Flags;
+
+            fn bitor(self, rhs: F) -> Flags {
+                Flags::from(self) | rhs.into()
+            }
+        }
+    };
+}
+
+impl_flag_option!(Symmetry);
+impl_flag_option!(Duplicates);
+impl_flag_option!(DiscardZero);
+impl_flag_option!(MajorOrder);
+impl_flag_option!(IndexBase);
+impl_flag_option!(StorageFormat);
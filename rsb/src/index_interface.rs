@@ -0,0 +1,116 @@
+use crate::{
+    common::*,
+    error::{Error, Result},
+    flag::Flags,
+};
+
+pub use sys::LONG_INDICES;
+
+/// The largest row or column index or dimension librsb can represent.
+pub const MAX_INDEX: usize = sys::rsb_coo_idx_t::MAX as usize;
+
+/// The largest number of nonzeros librsb can represent.
+pub const MAX_NNZ: usize = sys::rsb_nnz_idx_t::MAX as usize;
+
+/// Convert a row or column index or dimension to the librsb index type.
+pub fn to_coo_idx(value: usize) -> Result<sys::rsb_coo_idx_t> {
+    sys::rsb_coo_idx_t::try_from(value).map_err(|_| Error::IndexOverflow {
+        value,
+        max: MAX_INDEX,
+    })
+}
+
+/// Convert a nonzero count or offset to the librsb index type.
+pub fn to_nnz_idx(value: usize) -> Result<sys::rsb_nnz_idx_t> {
+    sys::rsb_nnz_idx_t::try_from(value).map_err(|_| Error::IndexOverflow {
+        value,
+        max: MAX_NNZ,
+    })
+}
+
+/// Convert a count such as a thread count to the librsb integer type.
+pub(crate) fn to_int(value: usize) -> Result<sys::rsb_int_t> {
+    sys::rsb_int_t::try_from(value).map_err(|_| Error::IndexOverflow {
+        value,
+        max: sys::rsb_int_t::MAX as usize,
+    })
+}
+
+/// Convert row or column indices to the librsb index type.
+pub fn to_coo_indices(values: &[usize]) -> Result<Vec<sys::rsb_coo_idx_t>> {
+    values.iter().map(|&value| to_coo_idx(value)).collect()
+}
+
+/// The types accepted for row and column indices, which are the librsb index
+/// type and `usize`.
+///
+/// `usize` indices are converted with [to_coo_idx], while librsb indices are
+/// passed through without copying.
+pub trait IndexType: Copy {
+    fn to_coo_indices(indices: &[Self]) -> Result<Cow<'_, [sys::rsb_coo_idx_t]>>;
+}
+
+impl IndexType for sys::rsb_coo_idx_t {
+    fn to_coo_indices(indices: &[Self]) -> Result<Cow<'_, [sys::rsb_coo_idx_t]>> {
+        Ok(Cow::Borrowed(indices))
+    }
+}
+
+impl IndexType for usize {
+    fn to_coo_indices(indices: &[Self]) -> Result<Cow<'_, [sys::rsb_coo_idx_t]>> {
+        Ok(Cow::Owned(to_coo_indices(indices)?))
+    }
+}
+
+/// The base of row and column indices exchanged with librsb.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum IndexBase {
+    /// 0-based indices as in C.
+    #[default]
+    Zero,
+    /// 1-based indices as in Fortran.
+    One,
+}
+
+impl IndexBase {
+    pub fn code(&self) -> sys::rsb_flags_t {
+        let flag = match self {
+            Self::Zero => sys::RSB_FLAG_C_INDICES_INTERFACE,
+            Self::One => sys::RSB_FLAG_FORTRAN_INDICES_INTERFACE,
+        };
+        flag as sys::rsb_flags_t
+    }
+
+    /// Decode the index base from matrix flags.
+    pub fn from_flags(flags: Flags) -> Self {
+        if flags.contains(Flags::FORTRAN_INDICES_INTERFACE) {
+            Self::One
+        } else {
+            Self::Zero
+        }
+    }
+
+    /// The index of the first row or column.
+    pub fn offset(&self) -> sys::rsb_coo_idx_t {
+        match self {
+            Self::Zero => 0,
+            Self::One => 1,
+        }
+    }
+}
+
+/// Convert an index array between the librsb index types, reusing the
+/// allocation when the types have the same size.
+pub(crate) fn convert_indices<A, B>(indices: Vec<A>) -> Result<Vec<B>>
+where
+    A: Copy + Display,
+    B: TryFrom<A>,
+{
+    indices
+        .into_iter()
+        .map(|index| {
+            B::try_from(index)
+                .map_err(|_| Error::custom(format!("the index {} does not fit in librsb", index)))
+        })
+        .collect()
+}
@@ -0,0 +1,15 @@
+use crate::{common::*, flag::Flags};
+
+/// The metadata of a [Mtx](crate::mtx::Mtx) reported by librsb.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixInfo {
+    pub nrows: usize,
+    pub ncols: usize,
+    pub nnz: usize,
+    pub flags: Flags,
+    pub type_code: sys::rsb_type_t,
+    pub leaves_count: usize,
+    pub total_size_bytes: usize,
+    pub index_storage_bytes: usize,
+    pub index_storage_bytes_per_nnz: f64,
+}
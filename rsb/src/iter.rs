@@ -0,0 +1,109 @@
+use crate::{common::*, error::Result, mtx::Mtx, numerical_type::NumericalType};
+use std::vec;
+
+/// The number of rows fetched from librsb at a time by [Iter].
+const ROW_BATCH: usize = 1024;
+
+/// An iterator over the `(row, col, value)` nonzeros of a range of rows.
+#[derive(Debug)]
+pub struct RowIter<T> {
+    rows: vec::IntoIter<sys::rsb_coo_idx_t>,
+    cols: vec::IntoIter<sys::rsb_coo_idx_t>,
+    vals: vec::IntoIter<T>,
+}
+
+impl<T> RowIter<T> {
+    pub(crate) fn new(
+        rows: Vec<sys::rsb_coo_idx_t>,
+        cols: Vec<sys::rsb_coo_idx_t>,
+        vals: Vec<T>,
+    ) -> Self {
+        Self {
+            rows: rows.into_iter(),
+            cols: cols.into_iter(),
+            vals: vals.into_iter(),
+        }
+    }
+
+    fn empty() -> Self {
+        Self::new(vec![], vec![], vec![])
+    }
+}
+
+impl<T> Iterator for RowIter<T> {
+    type Item = (usize, usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.rows.next()?;
+        let col = self.cols.next()?;
+        let val = self.vals.next()?;
+        Some((row as usize, col as usize, val))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.vals.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for RowIter<T> {}
+
+/// An iterator over all `(row, col, value)` nonzeros of a [Mtx].
+///
+/// The nonzeros are streamed from librsb in batches of rows. If librsb fails
+/// to extract a batch, the error is yielded once and the iteration stops.
+#[derive(Debug)]
+pub struct Iter<'a, T>
+where
+    T: NumericalType,
+{
+    mtx: &'a Mtx<T>,
+    next_row: usize,
+    nrows: usize,
+    batch: RowIter<T>,
+    failed: bool,
+}
+
+impl<'a, T> Iter<'a, T>
+where
+    T: NumericalType,
+{
+    pub(crate) fn new(mtx: &'a Mtx<T>, nrows: usize) -> Self {
+        Self {
+            mtx,
+            next_row: 0,
+            nrows,
+            batch: RowIter::empty(),
+            failed: false,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+where
+    T: NumericalType,
+{
+    type Item = Result<(usize, usize, T)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.batch.next() {
+                return Some(Ok(item));
+            }
+
+            if self.failed || self.next_row >= self.nrows {
+                return None;
+            }
+
+            let first = self.next_row;
+            let last = (first + ROW_BATCH).min(self.nrows) - 1;
+            self.next_row = last + 1;
+            match self.mtx.iter_rows(first..=last) {
+                Ok(batch) => self.batch = batch,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
@@ -1,15 +1,26 @@
+pub mod builder;
 mod common;
 pub mod discard_zero;
 pub mod duplicates;
+pub mod elopf;
 pub mod error;
 pub mod flag;
+pub mod index_interface;
+pub mod info;
 mod init;
+pub mod iter;
 pub mod major_order;
 pub mod mtx;
+pub mod norm;
 pub mod numerical_type;
+pub mod options;
+pub mod prec;
 pub mod repr;
+pub mod solve;
 pub mod spmm;
 pub mod storage;
 pub mod symmetry;
 pub mod transpose;
 mod utils;
+
+pub use num_complex;
@@ -2,20 +2,94 @@ use crate::{
     common::*,
     discard_zero::DiscardZero,
     duplicates::Duplicates,
+    elopf::ElementOp,
     error::{check, ensure, Error, Result, RSB_ERR_NO_ERROR},
-    numerical_type::NumericalType,
-    repr, storage,
+    flag::Flags,
+    index_interface::{convert_indices, to_coo_idx, to_int, to_nnz_idx, IndexBase, IndexType},
+    info::MatrixInfo,
+    iter::{Iter, RowIter},
+    major_order::MajorOrder,
+    norm::Norm,
+    numerical_type::{self, NumericalType},
+    options::MtxOptions,
+    repr,
+    spmm::{SpmmOptions, TuningMode, TuningReport},
+    storage::StorageFormat,
     symmetry::Symmetry,
     transpose::Transpose,
     utils,
 };
 
-#[derive(Debug)]
+type TuneFn = unsafe extern "C" fn(
+    *mut *mut sys::rsb_mtx_t,
+    *mut sys::rsb_real_t,
+    *mut sys::rsb_int_t,
+    sys::rsb_int_t,
+    sys::rsb_time_t,
+    sys::rsb_trans_t,
+    *const c_void,
+    *const sys::rsb_mtx_t,
+    sys::rsb_coo_idx_t,
+    sys::rsb_flags_t,
+    *const c_void,
+    sys::rsb_nnz_idx_t,
+    *const c_void,
+    *mut c_void,
+    sys::rsb_nnz_idx_t,
+) -> sys::rsb_err_t;
+
+type SwitchFn = unsafe extern "C" fn(
+    *mut sys::rsb_mtx_t,
+    *mut *mut c_void,
+    *mut *mut sys::rsb_coo_idx_t,
+    *mut *mut sys::rsb_coo_idx_t,
+    sys::rsb_flags_t,
+) -> sys::rsb_err_t;
+
+/// The caller-owned arrays an in-place matrix is stored in.
+enum InplaceArrays<T> {
+    Coo {
+        ia: Vec<sys::rsb_coo_idx_t>,
+        ja: Vec<sys::rsb_coo_idx_t>,
+        va: Vec<T>,
+    },
+    Csr {
+        rp: Vec<sys::rsb_nnz_idx_t>,
+        ja: Vec<sys::rsb_coo_idx_t>,
+        va: Vec<T>,
+    },
+}
+
+impl<T> InplaceArrays<T> {
+    /// The length of the array holding row indices or row pointers.
+    fn row_len(&self) -> usize {
+        match self {
+            Self::Coo { ia, .. } => ia.len(),
+            Self::Csr { rp, .. } => rp.len(),
+        }
+    }
+
+    /// The addresses of the `(va, ia, ja)` arrays.
+    fn as_ptrs(&self) -> [*const c_void; 3] {
+        let (va, ia, ja) = match self {
+            Self::Coo { ia, ja, va } => (va.as_ptr(), ia.as_ptr() as *const c_void, ja.as_ptr()),
+            Self::Csr { rp, ja, va } => (va.as_ptr(), rp.as_ptr() as *const c_void, ja.as_ptr()),
+        };
+        [va as *const c_void, ia, ja as *const c_void]
+    }
+}
+
+/// Matrices with at most this many rows and columns are previewed in dense
+/// form by the alternate `{:#}` format.
+const PREVIEW_MAX_DIM: usize = 8;
+
 pub struct Mtx<T>
 where
     T: NumericalType,
 {
     ptr: Option<NonNull<sys::rsb_mtx_t>>,
+    /// Kept alive until the librsb handle is freed.
+    arrays: Option<InplaceArrays<T>>,
     _phantom: PhantomData<T>,
 }
 
@@ -23,19 +97,34 @@ impl<T> Mtx<T>
 where
     T: NumericalType,
 {
-    pub fn try_from_coo_slices(
-        nr: sys::rsb_coo_idx_t,
-        nc: sys::rsb_coo_idx_t,
+    pub fn try_from_coo_slices<I, O>(
+        nr: usize,
+        nc: usize,
         va: &[T],
-        ia: &[sys::rsb_coo_idx_t],
-        ja: &[sys::rsb_coo_idx_t],
-        symmetry: Symmetry,
-        duplicates: Duplicates,
-    ) -> Result<Self> {
+        ia: &[I],
+        ja: &[I],
+        options: O,
+    ) -> Result<Self>
+    where
+        I: IndexType,
+        O: Into<MtxOptions>,
+    {
         crate::init::init();
 
         let nnz = va.len();
-        let flags = duplicates.code() | symmetry.code();
+        ensure!(
+            ia.len() == nnz && ja.len() == nnz,
+            "the row and column index slices have lengths {} and {}, but {} values are given",
+            ia.len(),
+            ja.len(),
+            nnz
+        );
+        let ia = I::to_coo_indices(ia)?;
+        let ja = I::to_coo_indices(ja)?;
+        let (nr, nc) = (to_coo_idx(nr)?, to_coo_idx(nc)?);
+        let options = options.into();
+        let flags = options.to_flags();
+        let (br, bc) = options.blocking()?;
 
         let mtx = unsafe {
             let mut errval = RSB_ERR_NO_ERROR;
@@ -43,35 +132,57 @@ where
                 va.as_ptr() as *const c_void,
                 ia.as_ptr(),
                 ja.as_ptr(),
-                nnz as sys::rsb_nnz_idx_t,
+                to_nnz_idx(nnz)?,
                 T::TYPE_CODE,
                 nr,
                 nc,
-                sys::RSB_DEFAULT_BLOCKING as sys::rsb_blk_idx_t,
-                sys::RSB_DEFAULT_BLOCKING as sys::rsb_blk_idx_t,
-                flags as sys::rsb_flags_t,
+                br,
+                bc,
+                flags.code(),
                 &mut errval as *mut _,
             );
-            check(errval)?;
+            check(errval, "try_from_coo_slices")?;
             mtx
         };
 
         unsafe { Ok(Mtx::from_raw(mtx)) }
     }
 
-    pub fn try_from_csc_slices(
-        nr: sys::rsb_coo_idx_t,
-        nc: sys::rsb_coo_idx_t,
+    pub fn try_from_csc_slices<I, O>(
+        nr: usize,
+        nc: usize,
         va: &[T],
-        ia: &[sys::rsb_coo_idx_t],
-        cp: &[sys::rsb_coo_idx_t],
-        symmetry: Symmetry,
-        duplicates: Duplicates,
-    ) -> Result<Self> {
+        ia: &[I],
+        cp: &[I],
+        options: O,
+    ) -> Result<Self>
+    where
+        I: IndexType,
+        O: Into<MtxOptions>,
+    {
         crate::init::init();
 
         let nnz = va.len();
-        let flags = duplicates.code() | symmetry.code();
+        ensure!(
+            ia.len() == nnz,
+            "the row index slice has length {}, but {} values are given",
+            ia.len(),
+            nnz
+        );
+        ensure!(
+            cp.len() == nc + 1,
+            "the column pointer slice has length {}, but {} is expected for {} columns",
+            cp.len(),
+            nc + 1,
+            nc
+        );
+        let ia = I::to_coo_indices(ia)?;
+        let cp = I::to_coo_indices(cp)?;
+        let (nr, nc) = (to_coo_idx(nr)?, to_coo_idx(nc)?);
+        let options = options.into();
+        let flags = options.to_flags();
+        check_pointers(&cp, nnz, IndexBase::from_flags(flags), "column")?;
+        let (br, bc) = options.blocking()?;
 
         let mtx = unsafe {
             let mut errval = RSB_ERR_NO_ERROR;
@@ -79,35 +190,57 @@ where
                 va.as_ptr() as *const c_void,
                 ia.as_ptr(),
                 cp.as_ptr(),
-                nnz as sys::rsb_nnz_idx_t,
+                to_nnz_idx(nnz)?,
                 T::TYPE_CODE,
                 nr,
                 nc,
-                sys::RSB_DEFAULT_BLOCKING as sys::rsb_blk_idx_t,
-                sys::RSB_DEFAULT_BLOCKING as sys::rsb_blk_idx_t,
-                flags as sys::rsb_flags_t,
+                br,
+                bc,
+                flags.code(),
                 &mut errval as *mut _,
             );
-            check(errval)?;
+            check(errval, "try_from_csc_slices")?;
             mtx
         };
 
         unsafe { Ok(Mtx::from_raw(mtx)) }
     }
 
-    pub fn try_from_csr_slices(
-        nr: sys::rsb_coo_idx_t,
-        nc: sys::rsb_coo_idx_t,
+    pub fn try_from_csr_slices<I, O>(
+        nr: usize,
+        nc: usize,
         va: &[T],
-        rp: &[sys::rsb_coo_idx_t],
-        ja: &[sys::rsb_coo_idx_t],
-        symmetry: Symmetry,
-        duplicates: Duplicates,
-    ) -> Result<Self> {
+        rp: &[I],
+        ja: &[I],
+        options: O,
+    ) -> Result<Self>
+    where
+        I: IndexType,
+        O: Into<MtxOptions>,
+    {
         crate::init::init();
 
         let nnz = va.len();
-        let flags = duplicates.code() | symmetry.code();
+        ensure!(
+            ja.len() == nnz,
+            "the column index slice has length {}, but {} values are given",
+            ja.len(),
+            nnz
+        );
+        ensure!(
+            rp.len() == nr + 1,
+            "the row pointer slice has length {}, but {} is expected for {} rows",
+            rp.len(),
+            nr + 1,
+            nr
+        );
+        let rp = I::to_coo_indices(rp)?;
+        let ja = I::to_coo_indices(ja)?;
+        let (nr, nc) = (to_coo_idx(nr)?, to_coo_idx(nc)?);
+        let options = options.into();
+        let flags = options.to_flags();
+        check_pointers(&rp, nnz, IndexBase::from_flags(flags), "row")?;
+        let (br, bc) = options.blocking()?;
 
         let mtx = unsafe {
             let mut errval = RSB_ERR_NO_ERROR;
@@ -115,22 +248,134 @@ where
                 va.as_ptr() as *const c_void,
                 rp.as_ptr(),
                 ja.as_ptr(),
-                nnz as sys::rsb_nnz_idx_t,
+                to_nnz_idx(nnz)?,
                 T::TYPE_CODE,
                 nr,
                 nc,
-                sys::RSB_DEFAULT_BLOCKING as sys::rsb_blk_idx_t,
-                sys::RSB_DEFAULT_BLOCKING as sys::rsb_blk_idx_t,
-                flags as sys::rsb_flags_t,
+                br,
+                bc,
+                flags.code(),
                 &mut errval as *mut _,
             );
-            check(errval)?;
+            check(errval, "try_from_csr_slices")?;
             mtx
         };
 
         unsafe { Ok(Mtx::from_raw(mtx)) }
     }
 
+    /// Build a matrix inside the given COO arrays without copying them.
+    ///
+    /// librsb reorders the arrays in place. The matrix owns them until it is
+    /// dropped.
+    pub fn try_from_coo_vecs<O>(
+        nr: usize,
+        nc: usize,
+        mut va: Vec<T>,
+        mut ia: Vec<sys::rsb_coo_idx_t>,
+        mut ja: Vec<sys::rsb_coo_idx_t>,
+        options: O,
+    ) -> Result<Self>
+    where
+        O: Into<MtxOptions>,
+    {
+        crate::init::init();
+
+        let nnz = va.len();
+        let (nr, nc) = (to_coo_idx(nr)?, to_coo_idx(nc)?);
+        ensure!(
+            ia.len() == nnz && ja.len() == nnz,
+            "the length of vecs must be equal"
+        );
+        let options = options.into();
+        let flags = options.to_flags();
+        let (br, bc) = options.blocking()?;
+
+        let mtx = unsafe {
+            let mut errval = RSB_ERR_NO_ERROR;
+            let mtx = sys::rsb_mtx_alloc_from_coo_inplace(
+                va.as_mut_ptr() as *mut c_void,
+                ia.as_mut_ptr(),
+                ja.as_mut_ptr(),
+                to_nnz_idx(nnz)?,
+                T::TYPE_CODE,
+                nr,
+                nc,
+                br,
+                bc,
+                flags.code(),
+                &mut errval as *mut _,
+            );
+            check(errval, "try_from_coo_vecs")?;
+            mtx
+        };
+
+        let mut mtx = unsafe { Mtx::from_raw(mtx) };
+        mtx.arrays = Some(InplaceArrays::Coo { ia, ja, va });
+        Ok(mtx)
+    }
+
+    /// Build a matrix inside the given CSR arrays without copying them.
+    ///
+    /// librsb reorders the arrays in place, and `rp` is grown to hold one row
+    /// index per nonzero. The matrix owns them until it is dropped.
+    pub fn try_from_csr_vecs<O>(
+        nr: usize,
+        nc: usize,
+        mut va: Vec<T>,
+        mut rp: Vec<sys::rsb_nnz_idx_t>,
+        mut ja: Vec<sys::rsb_coo_idx_t>,
+        options: O,
+    ) -> Result<Self>
+    where
+        O: Into<MtxOptions>,
+    {
+        crate::init::init();
+
+        let nnz = va.len();
+        ensure!(
+            ja.len() == nnz,
+            "the length of column indices and values must be equal"
+        );
+        ensure!(
+            rp.len() == nr + 1,
+            "the row pointers have {} elements, but {} rows are given",
+            rp.len(),
+            nr
+        );
+        let options = options.into();
+        let flags = options.to_flags();
+        check_pointers(&rp, nnz, IndexBase::from_flags(flags), "row")?;
+        // librsb expands the row pointers to one row index per nonzero in the
+        // same array.
+        rp.resize(rp.len().max(nnz), 0);
+        let (nr, nc) = (to_coo_idx(nr)?, to_coo_idx(nc)?);
+        let (br, bc) = options.blocking()?;
+
+        let mtx = unsafe {
+            let mut errval = RSB_ERR_NO_ERROR;
+            let mtx = sys::rsb_mtx_alloc_from_csr_inplace(
+                va.as_mut_ptr() as *mut c_void,
+                rp.as_mut_ptr(),
+                ja.as_mut_ptr(),
+                to_nnz_idx(nnz)?,
+                T::TYPE_CODE,
+                nr,
+                nc,
+                br,
+                bc,
+                flags.code(),
+                &mut errval as *mut _,
+            );
+            check(errval, "try_from_csr_vecs")?;
+            mtx
+        };
+
+        let mut mtx = unsafe { Mtx::from_raw(mtx) };
+        mtx.arrays = Some(InplaceArrays::Csr { rp, ja, va });
+        Ok(mtx)
+    }
+
     pub fn spmv<'a, A, R, B, O>(
         &self,
         transpose: Transpose,
@@ -161,18 +406,24 @@ where
         let rhs = rhs.into();
         let mut output = output.into();
 
+        self.check_spmv_shapes(&transpose, &rhs, &output)?;
+
         unsafe {
             let err = sys::rsb_spmv(
                 transpose.code(),
                 alpha_ptr as *const c_void,
                 self.ptr(),
                 rhs.to_ptr(),
-                rhs.stride(),
+                rhs.stride()?,
                 beta_ptr as *const c_void,
                 output.to_ptr(),
-                output.stride(),
+                output.stride()?,
             );
-            check(err)?;
+            check(err, "spmv").map_err(|err| {
+                self.error_with_shape(err, "matrix")
+                    .with_shape("rhs", [rhs.len(), 1])
+                    .with_shape("output", [output.len(), 1])
+            })?;
         }
 
         Ok(())
@@ -193,17 +444,24 @@ where
         let rhs = rhs.into();
         let mut output = output.into();
 
+        self.check_square("spsv")?;
+        self.check_spmv_shapes(&transpose, &rhs, &output)?;
+
         unsafe {
             let err = sys::rsb_spsv(
                 transpose.code(),
                 scale_ptr as *const c_void,
                 self.ptr(),
                 rhs.to_ptr(),
-                rhs.stride(),
+                rhs.stride()?,
                 output.to_ptr(),
-                output.stride(),
+                output.stride()?,
             );
-            check(err)?;
+            check(err, "spsv").map_err(|err| {
+                self.error_with_shape(err, "matrix")
+                    .with_shape("rhs", [rhs.len(), 1])
+                    .with_shape("output", [output.len(), 1])
+            })?;
         }
 
         Ok(())
@@ -236,30 +494,150 @@ where
             .unwrap_or_else(|| ptr::null());
         let rhs = rhs.into();
         let mut output = output.into();
-        ensure!(
-            rhs.order() == output.order(),
-            "rhs and output matrix major order must be the same"
-        );
+
+        self.check_square("spsm")?;
+        self.check_spmm_shapes(&transpose, &rhs, &output)?;
 
         unsafe {
             let err = sys::rsb_spsm(
                 transpose.code(),
                 alpha_ptr as *const c_void,
                 self.ptr(),
-                rhs.num_vecs(),
+                rhs.num_vecs()?,
                 rhs.order().code(),
                 beta_ptr as *const c_void,
                 rhs.to_ptr(),
-                rhs.leading_dimension(),
+                rhs.leading_dimension()?,
+                output.to_ptr(),
+                output.leading_dimension()?,
+            );
+            check(err, "spsm").map_err(|err| {
+                self.error_with_shape(err, "matrix")
+                    .with_shape("rhs", rhs.hw())
+                    .with_shape("output", output.hw())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn spmm<'a, A, B, R, O>(
+        &self,
+        options: &SpmmOptions,
+        alpha: A,
+        rhs: R,
+        beta: B,
+        output: O,
+    ) -> Result<()>
+    where
+        A: Into<Option<T>>,
+        B: Into<Option<T>>,
+        R: Into<repr::Matrix<'a, T>>,
+        O: Into<repr::MatrixMut<'a, T>>,
+        T: 'a,
+    {
+        let alpha = alpha.into();
+        let alpha_ptr = alpha
+            .as_ref()
+            .map(|v| v as *const T)
+            .unwrap_or_else(|| ptr::null());
+        let beta = beta.into();
+        let beta_ptr = beta
+            .as_ref()
+            .map(|v| v as *const T)
+            .unwrap_or_else(|| ptr::null());
+        let rhs = rhs.into();
+        let mut output = output.into();
+        let transpose = &options.transpose;
+
+        self.check_spmm_shapes(transpose, &rhs, &output)?;
+
+        unsafe {
+            let err = sys::rsb_spmm(
+                transpose.code(),
+                alpha_ptr as *const c_void,
+                self.ptr(),
+                rhs.num_vecs()?,
+                rhs.order().code(),
+                rhs.to_ptr(),
+                rhs.leading_dimension()?,
+                beta_ptr as *const c_void,
                 output.to_ptr(),
-                output.leading_dimension(),
+                output.leading_dimension()?,
             );
-            check(err)?;
+            check(err, "spmm").map_err(|err| {
+                self.error_with_shape(err, "matrix")
+                    .with_shape("rhs", rhs.hw())
+                    .with_shape("output", output.hw())
+            })?;
         }
 
         Ok(())
     }
 
+    pub fn tune_spmm<'a, A, B, R, O>(
+        &mut self,
+        mode: TuningMode,
+        options: &SpmmOptions,
+        alpha: A,
+        rhs: R,
+        beta: B,
+        output: O,
+    ) -> Result<TuningReport>
+    where
+        A: Into<Option<T>>,
+        B: Into<Option<T>>,
+        R: Into<repr::Matrix<'a, T>>,
+        O: Into<repr::MatrixMut<'a, T>>,
+        T: 'a,
+    {
+        let rhs = rhs.into();
+        let output = output.into();
+        self.check_spmm_shapes(&options.transpose, &rhs, &output)?;
+        self.tune(
+            "tune_spmm",
+            sys::rsb_tune_spmm,
+            mode,
+            options,
+            alpha.into(),
+            rhs,
+            beta.into(),
+            output,
+        )
+    }
+
+    pub fn tune_spsm<'a, A, B, R, O>(
+        &mut self,
+        mode: TuningMode,
+        options: &SpmmOptions,
+        alpha: A,
+        beta: B,
+        rhs: R,
+        output: O,
+    ) -> Result<TuningReport>
+    where
+        A: Into<Option<T>>,
+        B: Into<Option<T>>,
+        R: Into<repr::Matrix<'a, T>>,
+        O: Into<repr::MatrixMut<'a, T>>,
+        T: 'a,
+    {
+        let rhs = rhs.into();
+        let output = output.into();
+        self.check_square("tune_spsm")?;
+        self.check_spmm_shapes(&options.transpose, &rhs, &output)?;
+        self.tune(
+            "tune_spsm",
+            sys::rsb_tune_spsm,
+            mode,
+            options,
+            alpha.into(),
+            rhs,
+            beta.into(),
+            output,
+        )
+    }
+
     pub fn sppsp<A, B>(
         &self,
         self_transpose: Transpose,
@@ -284,6 +662,17 @@ where
             .map(|v| v as *const T)
             .unwrap_or_else(|| ptr::null());
 
+        let lhs_hw = transposed_dims(&self_transpose, self.dims()?);
+        let rhs_hw = transposed_dims(&rhs_transpose, rhs.dims()?);
+        ensure!(
+            lhs_hw == rhs_hw,
+            "cannot add a {}x{} matrix to a {}x{} matrix",
+            lhs_hw[0],
+            lhs_hw[1],
+            rhs_hw[0],
+            rhs_hw[1]
+        );
+
         unsafe {
             let mut errval = RSB_ERR_NO_ERROR;
 
@@ -298,7 +687,8 @@ where
                 &mut errval as *mut _,
             );
 
-            check(errval)?;
+            check(errval, "sppsp")
+                .map_err(|err| rhs.error_with_shape(self.error_with_shape(err, "lhs"), "rhs"))?;
 
             Ok(Self::from_raw(ptr))
         }
@@ -328,6 +718,17 @@ where
             .map(|v| v as *const T)
             .unwrap_or_else(|| ptr::null());
 
+        let [lhs_nr, lhs_nc] = transposed_dims(&self_transpose, self.dims()?);
+        let [rhs_nr, rhs_nc] = transposed_dims(&rhs_transpose, rhs.dims()?);
+        ensure!(
+            lhs_nc == rhs_nr,
+            "cannot multiply a {}x{} matrix with a {}x{} matrix",
+            lhs_nr,
+            lhs_nc,
+            rhs_nr,
+            rhs_nc
+        );
+
         unsafe {
             let mut errval = RSB_ERR_NO_ERROR;
 
@@ -342,20 +743,105 @@ where
                 &mut errval as *mut _,
             );
 
-            check(errval)?;
+            check(errval, "spmsp")
+                .map_err(|err| rhs.error_with_shape(self.error_with_shape(err, "lhs"), "rhs"))?;
 
             Ok(Self::from_raw(ptr))
         }
     }
 
+    /// Multiply two sparse matrices and add the product to a dense matrix.
+    pub fn spmsp_to_dense<'a, A, B, O>(
+        &self,
+        self_transpose: Transpose,
+        alpha: A,
+        rhs_transpose: Transpose,
+        beta: B,
+        rhs: &Self,
+        output: O,
+    ) -> Result<()>
+    where
+        A: Into<Option<T>>,
+        B: Into<Option<T>>,
+        O: Into<repr::MatrixMut<'a, T>>,
+        T: 'a,
+    {
+        let alpha = alpha.into();
+        let alpha_ptr = alpha
+            .as_ref()
+            .map(|v| v as *const T)
+            .unwrap_or_else(|| ptr::null());
+
+        let beta = beta.into();
+        let beta_ptr = beta
+            .as_ref()
+            .map(|v| v as *const T)
+            .unwrap_or_else(|| ptr::null());
+
+        let mut output = output.into();
+
+        let [lhs_nr, lhs_nc] = transposed_dims(&self_transpose, self.dims()?);
+        let [rhs_nr, rhs_nc] = transposed_dims(&rhs_transpose, rhs.dims()?);
+        ensure!(
+            lhs_nc == rhs_nr,
+            "cannot multiply a {}x{} matrix with a {}x{} matrix",
+            lhs_nr,
+            lhs_nc,
+            rhs_nr,
+            rhs_nc
+        );
+        ensure!(
+            output.hw() == [lhs_nr, rhs_nc],
+            "the output shape {:?} does not match the product shape {:?}",
+            output.hw(),
+            [lhs_nr, rhs_nc]
+        );
+        let row_major = match output.order() {
+            MajorOrder::Row => sys::RSB_BOOL_TRUE,
+            MajorOrder::Column => sys::RSB_BOOL_FALSE,
+        };
+
+        unsafe {
+            let err = sys::rsb_spmsp_to_dense(
+                T::TYPE_CODE,
+                self_transpose.code(),
+                alpha_ptr as *const c_void,
+                self.ptr(),
+                rhs_transpose.code(),
+                beta_ptr as *const c_void,
+                rhs.ptr(),
+                output.leading_dimension()?,
+                to_nnz_idx(lhs_nr)?,
+                to_nnz_idx(rhs_nc)?,
+                row_major as sys::rsb_bool_t,
+                output.to_ptr(),
+            );
+            check(err, "spmsp_to_dense").map_err(|err| {
+                rhs.error_with_shape(self.error_with_shape(err, "lhs"), "rhs")
+                    .with_shape("output", output.hw())
+            })?;
+        }
+
+        Ok(())
+    }
+
     pub unsafe fn from_raw(ptr: *mut sys::rsb_mtx_t) -> Self {
         Self {
             ptr: Some(NonNull::new(ptr).unwrap()),
+            arrays: None,
             _phantom: PhantomData,
         }
     }
 
+    /// Release the librsb handle to the caller.
+    ///
+    /// The arrays of a matrix built by [try_from_coo_vecs](Mtx::try_from_coo_vecs)
+    /// or [try_from_csr_vecs](Mtx::try_from_csr_vecs) are leaked so that the
+    /// handle stays valid.
     pub fn into_raw(mut self) -> *mut sys::rsb_mtx_t {
+        if let Some(arrays) = self.arrays.take() {
+            mem::forget(arrays);
+        }
         unsafe { self.ptr.take().unwrap().as_mut() }
     }
 
@@ -387,26 +873,32 @@ where
                 self.ptr(),
                 flags as sys::rsb_flags_t,
             );
-            check(err)?;
+            check(err, "convert_to")?;
             ptr
         };
 
         unsafe { Ok(Mtx::from_raw(ptr)) }
     }
 
-    pub fn extend_by_coo_slices(
+    pub fn extend_by_coo_slices<I>(
         &mut self,
-        rows: &[sys::rsb_coo_idx_t],
-        cols: &[sys::rsb_coo_idx_t],
+        rows: &[I],
+        cols: &[I],
         vals: &[T],
         duplicates: Duplicates,
-    ) -> Result<()> {
+        index_base: IndexBase,
+    ) -> Result<()>
+    where
+        I: IndexType,
+    {
         let nnz = rows.len();
         ensure!(
             cols.len() == nnz && vals.len() == nnz,
             "the length of slices must be equal"
         );
-        let flags = duplicates.code();
+        let rows = I::to_coo_indices(rows)?;
+        let cols = I::to_coo_indices(cols)?;
+        let flags = duplicates.code() | index_base.code();
 
         unsafe {
             let err = sys::rsb_mtx_set_vals(
@@ -414,26 +906,32 @@ where
                 vals.as_ptr() as *const c_void,
                 rows.as_ptr(),
                 cols.as_ptr(),
-                nnz as sys::rsb_nnz_idx_t,
+                to_nnz_idx(nnz)?,
                 flags,
             );
-            check(err)?;
+            check(err, "extend_by_coo_slices")?;
         }
 
         Ok(())
     }
 
-    pub fn get_by_coo_slices(
+    pub fn get_by_coo_slices<I>(
         &self,
         values: &mut [T],
-        rows: &[sys::rsb_coo_idx_t],
-        cols: &[sys::rsb_coo_idx_t],
-    ) -> Result<()> {
+        rows: &[I],
+        cols: &[I],
+        index_base: IndexBase,
+    ) -> Result<()>
+    where
+        I: IndexType,
+    {
         let nnz = rows.len();
         ensure!(
             nnz == cols.len() && nnz == values.len(),
             "the length of COO slices must be equal"
         );
+        let rows = I::to_coo_indices(rows)?;
+        let cols = I::to_coo_indices(cols)?;
 
         unsafe {
             let err = sys::rsb_mtx_get_vals(
@@ -441,76 +939,702 @@ where
                 values.as_mut_ptr() as *mut c_void,
                 rows.as_ptr(),
                 cols.as_ptr(),
-                nnz as sys::rsb_nnz_idx_t,
-                sys::RSB_FLAG_C_INDICES_INTERFACE as sys::rsb_flags_t,
+                to_nnz_idx(nnz)?,
+                index_base.code(),
             );
-            check(err)?;
+            check(err, "get_by_coo_slices")?;
         }
 
         Ok(())
     }
 
-    pub fn get(&self, row: usize, col: usize) -> Result<T> {
-        let mut values = [T::zero()];
-        let rows = [row as sys::rsb_coo_idx_t];
-        let cols = [col as sys::rsb_coo_idx_t];
-        self.get_by_coo_slices(&mut values, &rows, &cols)?;
-        Ok(values.into_iter().next().unwrap())
-    }
+    /// Export the stored nonzeros to owned `(rows, cols, vals)` triplets.
+    ///
+    /// Symmetric and hermitian matrices store only one triangle. If
+    /// `expand_symmetry` is set, the mirrored off-diagonal entries are appended
+    /// so that the triplets describe the full pattern.
+    pub fn to_coo(
+        &self,
+        index_base: IndexBase,
+        expand_symmetry: bool,
+    ) -> Result<(Vec<sys::rsb_coo_idx_t>, Vec<sys::rsb_coo_idx_t>, Vec<T>)> {
+        let nnz = self.nnz()?;
+        let mut rows: Vec<sys::rsb_coo_idx_t> = vec![0; nnz];
+        let mut cols: Vec<sys::rsb_coo_idx_t> = vec![0; nnz];
+        let mut vals: Vec<T> = vec![T::zero(); nnz];
 
-    pub fn mul_scalar(&mut self, mut scalar: T) -> Result<()> {
         unsafe {
-            let err = sys::rsb_mtx_upd_vals(
-                self.ptr_mut(),
-                sys::rsb_elopf_t::RSB_ELOPF_MUL,
-                &mut scalar as *mut T as *mut c_void,
+            let err = sys::rsb_mtx_get_coo(
+                self.ptr(),
+                vals.as_mut_ptr() as *mut c_void,
+                rows.as_mut_ptr(),
+                cols.as_mut_ptr(),
+                index_base.code(),
             );
-            check(err)?;
+            check(err, "to_coo")?;
         }
 
-        Ok(())
+        if expand_symmetry {
+            let flags = self.flags()?;
+            let hermitian = flags.contains(Flags::HERMITIAN);
+
+            if hermitian || flags.contains(Flags::SYMMETRIC) {
+                for idx in 0..nnz {
+                    let (row, col, val) = (rows[idx], cols[idx], vals[idx]);
+                    if row == col {
+                        continue;
+                    }
+                    rows.push(col);
+                    cols.push(row);
+                    vals.push(if hermitian { val.conj() } else { val });
+                }
+            }
+        }
+
+        Ok((rows, cols, vals))
     }
 
-    pub fn div_scalar(&mut self, mut scalar: T) -> Result<()> {
+    /// Export the matrix to owned CSR arrays `(row_ptrs, col_indices, vals)`.
+    pub fn to_csr(
+        &self,
+        index_base: IndexBase,
+    ) -> Result<(Vec<sys::rsb_nnz_idx_t>, Vec<sys::rsb_coo_idx_t>, Vec<T>)> {
+        let nr = self.nrows()?;
+        let nnz = self.nnz()?;
+        let mut rp: Vec<sys::rsb_nnz_idx_t> = vec![0; nr + 1];
+        let mut ja: Vec<sys::rsb_coo_idx_t> = vec![0; nnz];
+        let mut va: Vec<T> = vec![T::zero(); nnz];
+
         unsafe {
-            let err = sys::rsb_mtx_upd_vals(
-                self.ptr_mut(),
-                sys::rsb_elopf_t::RSB_ELOPF_DIV,
-                &mut scalar as *mut T as *mut c_void,
+            let err = sys::rsb_mtx_get_csr(
+                T::TYPE_CODE,
+                self.ptr(),
+                va.as_mut_ptr() as *mut c_void,
+                rp.as_mut_ptr(),
+                ja.as_mut_ptr(),
+                index_base.code(),
             );
-            check(err)?;
+            check(err, "to_csr")?;
         }
 
-        Ok(())
+        Ok((rp, ja, va))
     }
 
-    pub fn pow(&mut self, mut pow: T) -> Result<()> {
-        unsafe {
-            let err = sys::rsb_mtx_upd_vals(
-                self.ptr_mut(),
-                sys::rsb_elopf_t::RSB_ELOPF_POW,
-                &mut pow as *mut T as *mut c_void,
-            );
-            check(err)?;
+    /// Export the matrix to owned CSC arrays `(col_ptrs, row_indices, vals)`.
+    ///
+    /// The arrays are obtained by extracting the CSR form of the transposed matrix.
+    pub fn to_csc(
+        &self,
+        index_base: IndexBase,
+    ) -> Result<(Vec<sys::rsb_nnz_idx_t>, Vec<sys::rsb_coo_idx_t>, Vec<T>)> {
+        let transposed: Self = self.convert_to(None, Transpose::Transpose, false)?;
+        transposed.to_csr(index_base)
+    }
+
+    /// Consume the matrix and return its arrays as COO `(rows, cols, vals)`.
+    ///
+    /// A matrix built by [try_from_coo_vecs](Mtx::try_from_coo_vecs) or
+    /// [try_from_csr_vecs](Mtx::try_from_csr_vecs) is switched to COO inside the
+    /// vecs it was assembled in, without copying. Other matrices are copied.
+    pub fn into_coo(
+        self,
+        index_base: IndexBase,
+    ) -> Result<(Vec<sys::rsb_coo_idx_t>, Vec<sys::rsb_coo_idx_t>, Vec<T>)> {
+        if self.arrays.is_none() {
+            return self.to_coo(index_base, false);
         }
 
-        Ok(())
+        let nnz = self.nnz()?;
+        let arrays = self.switch_to("into_coo", sys::rsb_mtx_switch_to_coo, index_base)?;
+        let (mut rows, mut cols, mut vals) = match arrays {
+            InplaceArrays::Coo { ia, ja, va } => (ia, ja, va),
+            InplaceArrays::Csr { rp, ja, va } => (convert_indices(rp)?, ja, va),
+        };
+        rows.truncate(nnz);
+        cols.truncate(nnz);
+        vals.truncate(nnz);
+
+        Ok((rows, cols, vals))
     }
 
-    pub fn neg_inplace(&mut self) -> Result<()> {
-        unsafe {
-            let err = sys::rsb_mtx_upd_vals(
-                self.ptr_mut(),
-                sys::rsb_elopf_t::RSB_ELOPF_NEG,
-                ptr::null_mut(),
-            );
-            check(err)?;
+    /// Consume the matrix and return its arrays as CSR
+    /// `(row_ptrs, col_indices, vals)`.
+    ///
+    /// A matrix built by [try_from_coo_vecs](Mtx::try_from_coo_vecs) or
+    /// [try_from_csr_vecs](Mtx::try_from_csr_vecs) is switched to CSR inside the
+    /// vecs it was assembled in, without copying, if the row index vec can hold
+    /// the row pointers. Other matrices are copied.
+    pub fn into_csr(
+        self,
+        index_base: IndexBase,
+    ) -> Result<(Vec<sys::rsb_nnz_idx_t>, Vec<sys::rsb_coo_idx_t>, Vec<T>)> {
+        let nr = self.nrows()?;
+        let nnz = self.nnz()?;
+
+        match &self.arrays {
+            Some(arrays) if arrays.row_len() > nr => {}
+            _ => return self.to_csr(index_base),
         }
 
-        Ok(())
+        let arrays = self.switch_to("into_csr", sys::rsb_mtx_switch_to_csr, index_base)?;
+        let (mut row_ptrs, mut cols, mut vals) = match arrays {
+            InplaceArrays::Coo { ia, ja, va } => (convert_indices(ia)?, ja, va),
+            InplaceArrays::Csr { rp, ja, va } => (rp, ja, va),
+        };
+        row_ptrs.truncate(nr + 1);
+        cols.truncate(nnz);
+        vals.truncate(nnz);
+
+        Ok((row_ptrs, cols, vals))
     }
 
-    pub fn save<P>(&self, path: P) -> Result<()>
+    /// Iterate over all stored `(row, col, value)` nonzeros.
+    ///
+    /// Each item is a [Result] because the nonzeros are read from librsb as
+    /// the iteration proceeds.
+    pub fn iter(&self) -> Result<Iter<'_, T>> {
+        Ok(Iter::new(self, self.nrows()?))
+    }
+
+    /// Iterate over the stored nonzeros of a row.
+    pub fn iter_row(&self, row: usize) -> Result<RowIter<T>> {
+        self.iter_rows(row..=row)
+    }
+
+    /// Iterate over the stored nonzeros of an inclusive range of rows.
+    pub fn iter_rows(&self, rows: RangeInclusive<usize>) -> Result<RowIter<T>> {
+        let nr = self.nrows()?;
+        let (first, last) = rows.into_inner();
+        ensure!(
+            first <= last && last < nr,
+            "the row range {}..={} is out of bound of {} rows",
+            first,
+            last,
+            nr
+        );
+
+        let first = to_coo_idx(first)?;
+        let last = to_coo_idx(last)?;
+        let flags = IndexBase::Zero.code();
+
+        unsafe {
+            // Count the nonzeros first to size the buffers.
+            let mut nnz: sys::rsb_nnz_idx_t = 0;
+            let err = sys::rsb_mtx_get_rows_sparse(
+                Transpose::None.code(),
+                ptr::null(),
+                self.ptr(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                first,
+                last,
+                &mut nnz as *mut _,
+                flags,
+            );
+            check(err, "iter_rows")?;
+
+            let len = nnz as usize;
+            let mut rows: Vec<sys::rsb_coo_idx_t> = vec![0; len];
+            let mut cols: Vec<sys::rsb_coo_idx_t> = vec![0; len];
+            let mut vals: Vec<T> = vec![T::zero(); len];
+
+            let err = sys::rsb_mtx_get_rows_sparse(
+                Transpose::None.code(),
+                ptr::null(),
+                self.ptr(),
+                vals.as_mut_ptr() as *mut c_void,
+                rows.as_mut_ptr(),
+                cols.as_mut_ptr(),
+                first,
+                last,
+                &mut nnz as *mut _,
+                flags,
+            );
+            check(err, "iter_rows")?;
+
+            Ok(RowIter::new(rows, cols, vals))
+        }
+    }
+
+    /// Extract the block `A[rows, cols]` as a new general matrix.
+    ///
+    /// The mirrored entries of symmetric and hermitian matrices are included,
+    /// see [block_coo](Mtx::block_coo).
+    pub fn block(&self, rows: Range<usize>, cols: Range<usize>) -> Result<Self> {
+        let nr = rows.len();
+        let nc = cols.len();
+        let (ia, ja, va) = self.block_coo(rows, cols)?;
+        Self::try_from_coo_slices(
+            nr,
+            nc,
+            &va,
+            &ia,
+            &ja,
+            Symmetry::General | Duplicates::default(),
+        )
+    }
+
+    /// Extract the nonzeros of the block `A[rows, cols]` as owned
+    /// `(rows, cols, vals)` triplets, with indices relative to the block.
+    ///
+    /// For symmetric and hermitian matrices, the entries mirrored from the
+    /// stored triangle are included, conjugated for hermitian matrices, as in
+    /// [to_coo](Mtx::to_coo) with `expand_symmetry` set.
+    pub fn block_coo(
+        &self,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Result<(Vec<sys::rsb_coo_idx_t>, Vec<sys::rsb_coo_idx_t>, Vec<T>)> {
+        let [nr, nc] = self.dims()?;
+        ensure!(
+            rows.start <= rows.end && rows.end <= nr,
+            "the row range {:?} is out of bound of {} rows",
+            rows,
+            nr
+        );
+        ensure!(
+            cols.start <= cols.end && cols.end <= nc,
+            "the column range {:?} is out of bound of {} columns",
+            cols,
+            nc
+        );
+
+        if rows.is_empty() || cols.is_empty() {
+            return Ok((vec![], vec![], vec![]));
+        }
+
+        let (mut ia, mut ja, mut va) = self.stored_block_coo(&rows, &cols)?;
+
+        let flags = self.flags()?;
+        let hermitian = flags.contains(Flags::HERMITIAN);
+        if hermitian || flags.contains(Flags::SYMMETRIC) {
+            // The entries of A[rows, cols] in the other triangle are stored
+            // transposed in A[cols, rows].
+            let (mirror_ia, mirror_ja, mirror_va) = self.stored_block_coo(&cols, &rows)?;
+            for ((row, col), val) in mirror_ia.into_iter().zip(mirror_ja).zip(mirror_va) {
+                if row == col {
+                    continue;
+                }
+                ia.push(col);
+                ja.push(row);
+                va.push(if hermitian { val.conj() } else { val });
+            }
+        }
+
+        let fr = to_coo_idx(rows.start)?;
+        let fc = to_coo_idx(cols.start)?;
+        ia.iter_mut().for_each(|row| *row -= fr);
+        ja.iter_mut().for_each(|col| *col -= fc);
+
+        Ok((ia, ja, va))
+    }
+
+    /// Get the stored nonzeros of a non-empty block with absolute indices.
+    fn stored_block_coo(
+        &self,
+        rows: &Range<usize>,
+        cols: &Range<usize>,
+    ) -> Result<(Vec<sys::rsb_coo_idx_t>, Vec<sys::rsb_coo_idx_t>, Vec<T>)> {
+        // librsb takes inclusive bounds.
+        let fr = to_coo_idx(rows.start)?;
+        let lr = to_coo_idx(rows.end - 1)?;
+        let fc = to_coo_idx(cols.start)?;
+        let lc = to_coo_idx(cols.end - 1)?;
+        let flags = IndexBase::Zero.code();
+
+        unsafe {
+            // Count the nonzeros first to size the buffers.
+            let mut nnz: sys::rsb_nnz_idx_t = 0;
+            let err = sys::rsb_mtx_get_coo_block(
+                self.ptr(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                fr,
+                lr,
+                fc,
+                lc,
+                ptr::null(),
+                ptr::null(),
+                &mut nnz as *mut _,
+                flags,
+            );
+            check(err, "block_coo")?;
+
+            let len = nnz as usize;
+            let mut ia: Vec<sys::rsb_coo_idx_t> = vec![0; len];
+            let mut ja: Vec<sys::rsb_coo_idx_t> = vec![0; len];
+            let mut va: Vec<T> = vec![T::zero(); len];
+
+            let err = sys::rsb_mtx_get_coo_block(
+                self.ptr(),
+                va.as_mut_ptr() as *mut c_void,
+                ia.as_mut_ptr(),
+                ja.as_mut_ptr(),
+                fr,
+                lr,
+                fc,
+                lc,
+                ptr::null(),
+                ptr::null(),
+                &mut nnz as *mut _,
+                flags,
+            );
+            check(err, "block_coo")?;
+
+            Ok((ia, ja, va))
+        }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Result<T> {
+        let mut values = [T::zero()];
+        self.get_by_coo_slices(&mut values, &[row], &[col], IndexBase::Zero)?;
+        Ok(values.into_iter().next().unwrap())
+    }
+
+    /// Compute the main diagonal.
+    pub fn diagonal(&self) -> Result<Vec<T>> {
+        let mut output = vec![T::zero(); self.nrows()?];
+        self.diagonal_into(output.as_mut_slice())?;
+        Ok(output)
+    }
+
+    /// Compute the main diagonal into `output`.
+    pub fn diagonal_into<'a, O>(&self, output: O) -> Result<()>
+    where
+        O: Into<repr::VectorMut<'a, T>>,
+        T: 'a,
+    {
+        let len = self.nrows()?;
+        self.get_vec(
+            "diagonal",
+            sys::rsb_extff_t::RSB_EXTF_DIAG,
+            len,
+            output.into(),
+        )
+    }
+
+    /// Compute the sum of each row.
+    pub fn row_sums(&self) -> Result<Vec<T>> {
+        let mut output = vec![T::zero(); self.nrows()?];
+        self.row_sums_into(output.as_mut_slice())?;
+        Ok(output)
+    }
+
+    /// Compute the sum of each row into `output`.
+    pub fn row_sums_into<'a, O>(&self, output: O) -> Result<()>
+    where
+        O: Into<repr::VectorMut<'a, T>>,
+        T: 'a,
+    {
+        let len = self.nrows()?;
+        self.get_vec(
+            "row_sums",
+            sys::rsb_extff_t::RSB_EXTF_SUMS_ROW,
+            len,
+            output.into(),
+        )
+    }
+
+    /// Compute the sum of each column.
+    pub fn col_sums(&self) -> Result<Vec<T>> {
+        let mut output = vec![T::zero(); self.ncols()?];
+        self.col_sums_into(output.as_mut_slice())?;
+        Ok(output)
+    }
+
+    /// Compute the sum of each column into `output`.
+    pub fn col_sums_into<'a, O>(&self, output: O) -> Result<()>
+    where
+        O: Into<repr::VectorMut<'a, T>>,
+        T: 'a,
+    {
+        let len = self.ncols()?;
+        self.get_vec(
+            "col_sums",
+            sys::rsb_extff_t::RSB_EXTF_SUMS_COL,
+            len,
+            output.into(),
+        )
+    }
+
+    /// Compute the sum of absolute values of each row.
+    pub fn row_abs_sums(&self) -> Result<Vec<T>> {
+        let mut output = vec![T::zero(); self.nrows()?];
+        self.row_abs_sums_into(output.as_mut_slice())?;
+        Ok(output)
+    }
+
+    /// Compute the sum of absolute values of each row into `output`.
+    pub fn row_abs_sums_into<'a, O>(&self, output: O) -> Result<()>
+    where
+        O: Into<repr::VectorMut<'a, T>>,
+        T: 'a,
+    {
+        let len = self.nrows()?;
+        self.get_vec(
+            "row_abs_sums",
+            sys::rsb_extff_t::RSB_EXTF_ASUMS_ROW,
+            len,
+            output.into(),
+        )
+    }
+
+    /// Compute the sum of absolute values of each column.
+    pub fn col_abs_sums(&self) -> Result<Vec<T>> {
+        let mut output = vec![T::zero(); self.ncols()?];
+        self.col_abs_sums_into(output.as_mut_slice())?;
+        Ok(output)
+    }
+
+    /// Compute the sum of absolute values of each column into `output`.
+    pub fn col_abs_sums_into<'a, O>(&self, output: O) -> Result<()>
+    where
+        O: Into<repr::VectorMut<'a, T>>,
+        T: 'a,
+    {
+        let len = self.ncols()?;
+        self.get_vec(
+            "col_abs_sums",
+            sys::rsb_extff_t::RSB_EXTF_ASUMS_COL,
+            len,
+            output.into(),
+        )
+    }
+
+    /// Compute the matrix norm. The norm of a complex matrix is real.
+    pub fn norm(&self, kind: Norm) -> Result<T::Real> {
+        // A slot of the matrix type is large enough for the norm, whose value
+        // lies in the real part.
+        let mut value = T::zero();
+
+        unsafe {
+            let err =
+                sys::rsb_mtx_get_nrm(self.ptr(), &mut value as *mut T as *mut c_void, kind.code());
+            check(err, "norm")?;
+        }
+
+        Ok(value.re())
+    }
+
+    /// Add `alpha` times the matrix to a dense matrix of the same shape.
+    pub fn add_to_dense<'a, A, O>(&self, alpha: A, dense: O) -> Result<()>
+    where
+        A: Into<Option<T>>,
+        O: Into<repr::MatrixMut<'a, T>>,
+        T: 'a,
+    {
+        let alpha = alpha.into();
+        let alpha_ptr = alpha
+            .as_ref()
+            .map(|v| v as *const T)
+            .unwrap_or_else(|| ptr::null());
+        let mut dense = dense.into();
+
+        let [nr, nc] = self.dims()?;
+        ensure!(
+            dense.hw() == [nr, nc],
+            "the dense matrix shape {:?} does not match the sparse matrix shape {:?}",
+            dense.hw(),
+            [nr, nc]
+        );
+        let row_major = match dense.order() {
+            MajorOrder::Row => sys::RSB_BOOL_TRUE,
+            MajorOrder::Column => sys::RSB_BOOL_FALSE,
+        };
+
+        unsafe {
+            let err = sys::rsb_mtx_add_to_dense(
+                alpha_ptr as *const c_void,
+                self.ptr(),
+                dense.leading_dimension()?,
+                to_nnz_idx(nr)?,
+                to_nnz_idx(nc)?,
+                row_major as sys::rsb_bool_t,
+                dense.to_ptr(),
+            );
+            check(err, "add_to_dense").map_err(|err| {
+                self.error_with_shape(err, "matrix")
+                    .with_shape("dense", dense.hw())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Materialize the matrix to a contiguous dense array in the given order.
+    pub fn to_dense(&self, order: MajorOrder) -> Result<Vec<T>> {
+        let [nr, nc] = self.dims()?;
+        let mut dense = vec![T::zero(); nr * nc];
+        self.add_to_dense(
+            None,
+            repr::MatrixMut::from_slice(nr, nc, order, &mut dense)?,
+        )?;
+        Ok(dense)
+    }
+
+    /// Apply an element-wise update to the nonzeros.
+    pub fn update_values(&mut self, op: ElementOp<'_, T>) -> Result<()> {
+        match op {
+            ElementOp::ScaleRows(slice) => {
+                self.check_scale_len(slice.len(), self.nrows()?, "row")?
+            }
+            ElementOp::ScaleCols(slice) => {
+                self.check_scale_len(slice.len(), self.ncols()?, "column")?
+            }
+            ElementOp::ScaleRowsReal(slice) => {
+                self.check_scale_len(slice.len(), self.nrows()?, "row")?
+            }
+            ElementOp::ScaleColsReal(slice) => {
+                self.check_scale_len(slice.len(), self.ncols()?, "column")?
+            }
+            ElementOp::Mul(_) | ElementOp::Div(_) | ElementOp::Pow(_) | ElementOp::Neg => {}
+        }
+
+        unsafe {
+            let err = sys::rsb_mtx_upd_vals(self.ptr_mut(), op.code(), op.as_ptr());
+            check(err, "update_values")?;
+        }
+
+        Ok(())
+    }
+
+    pub fn mul_scalar(&mut self, scalar: T) -> Result<()> {
+        self.update_values(ElementOp::Mul(scalar))
+    }
+
+    pub fn div_scalar(&mut self, scalar: T) -> Result<()> {
+        self.update_values(ElementOp::Div(scalar))
+    }
+
+    pub fn pow(&mut self, pow: T) -> Result<()> {
+        self.update_values(ElementOp::Pow(pow))
+    }
+
+    pub fn neg_inplace(&mut self) -> Result<()> {
+        self.update_values(ElementOp::Neg)
+    }
+
+    /// Multiply the i-th row by `scales[i]`.
+    pub fn scale_rows(&mut self, scales: &[T]) -> Result<()> {
+        self.update_values(ElementOp::ScaleRows(scales))
+    }
+
+    /// Multiply the j-th column by `scales[j]`.
+    pub fn scale_cols(&mut self, scales: &[T]) -> Result<()> {
+        self.update_values(ElementOp::ScaleCols(scales))
+    }
+
+    /// Multiply the i-th row by the real `scales[i]`.
+    pub fn scale_rows_real(&mut self, scales: &[T::Real]) -> Result<()> {
+        self.update_values(ElementOp::ScaleRowsReal(scales))
+    }
+
+    /// Multiply the j-th column by the real `scales[j]`.
+    pub fn scale_cols_real(&mut self, scales: &[T::Real]) -> Result<()> {
+        self.update_values(ElementOp::ScaleColsReal(scales))
+    }
+
+    pub fn nrows(&self) -> Result<usize> {
+        let nr: sys::rsb_coo_idx_t =
+            unsafe { self.get_info(sys::rsb_mif_t::RSB_MIF_MATRIX_ROWS__TO__RSB_COO_INDEX_T, 0)? };
+        Ok(nr as usize)
+    }
+
+    pub fn ncols(&self) -> Result<usize> {
+        let nc: sys::rsb_coo_idx_t =
+            unsafe { self.get_info(sys::rsb_mif_t::RSB_MIF_MATRIX_COLS__TO__RSB_COO_INDEX_T, 0)? };
+        Ok(nc as usize)
+    }
+
+    pub fn nnz(&self) -> Result<usize> {
+        let nnz: sys::rsb_nnz_idx_t =
+            unsafe { self.get_info(sys::rsb_mif_t::RSB_MIF_MATRIX_NNZ__TO__RSB_NNZ_INDEX_T, 0)? };
+        Ok(nnz as usize)
+    }
+
+    pub fn flags(&self) -> Result<Flags> {
+        let flags: sys::rsb_flags_t =
+            unsafe { self.get_info(sys::rsb_mif_t::RSB_MIF_MATRIX_FLAGS__TO__RSB_FLAGS_T, 0)? };
+        Ok(Flags::from_bits_truncate(flags))
+    }
+
+    pub fn type_code(&self) -> Result<sys::rsb_type_t> {
+        unsafe {
+            self.get_info(
+                sys::rsb_mif_t::RSB_MIF_MATRIX_TYPECODE__TO__RSB_TYPE_T,
+                sys::rsb_type_t(0),
+            )
+        }
+    }
+
+    pub fn leaves_count(&self) -> Result<usize> {
+        let count: sys::rsb_blk_idx_t =
+            unsafe { self.get_info(sys::rsb_mif_t::RSB_MIF_LEAVES_COUNT__TO__RSB_BLK_INDEX_T, 0)? };
+        Ok(count as usize)
+    }
+
+    pub fn total_size_bytes(&self) -> Result<usize> {
+        let size: sys::size_t =
+            unsafe { self.get_info(sys::rsb_mif_t::RSB_MIF_TOTAL_SIZE__TO__SIZE_T, 0)? };
+        Ok(size as usize)
+    }
+
+    pub fn index_storage_bytes(&self) -> Result<usize> {
+        let size: sys::size_t = unsafe {
+            self.get_info(
+                sys::rsb_mif_t::RSB_MIF_INDEX_STORAGE_IN_BYTES__TO__SIZE_T,
+                0,
+            )?
+        };
+        Ok(size as usize)
+    }
+
+    pub fn index_storage_bytes_per_nnz(&self) -> Result<f64> {
+        unsafe {
+            self.get_info(
+                sys::rsb_mif_t::RSB_MIF_INDEX_STORAGE_IN_BYTES_PER_NNZ__TO__RSB_REAL_T,
+                0.0,
+            )
+        }
+    }
+
+    pub fn info(&self) -> Result<MatrixInfo> {
+        Ok(MatrixInfo {
+            nrows: self.nrows()?,
+            ncols: self.ncols()?,
+            nnz: self.nnz()?,
+            flags: self.flags()?,
+            type_code: self.type_code()?,
+            leaves_count: self.leaves_count()?,
+            total_size_bytes: self.total_size_bytes()?,
+            index_storage_bytes: self.index_storage_bytes()?,
+            index_storage_bytes_per_nnz: self.index_storage_bytes_per_nnz()?,
+        })
+    }
+
+    pub fn info_string(&self) -> Result<String> {
+        const KEY: &[u8] = b"RSB_MIF_MATRIX_INFO__TO__CHAR_P\0";
+        let mut buf = vec![0 as c_char; 1024];
+
+        unsafe {
+            let err = sys::rsb_mtx_get_info_str(
+                self.ptr(),
+                KEY.as_ptr() as *const c_char,
+                buf.as_mut_ptr() as *mut c_void,
+                buf.len() as sys::size_t,
+            );
+            check(err, "info_string")?;
+        }
+
+        // Make sure the string is terminated even if librsb filled the buffer.
+        *buf.last_mut().unwrap() = 0;
+        let text = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        Ok(text.to_string_lossy().into_owned())
+    }
+
+    pub fn save<P>(&self, path: P) -> Result<()>
     where
         P: AsRef<Path>,
     {
@@ -520,32 +1644,316 @@ where
 
         unsafe {
             let err = sys::rsb_file_mtx_save(self.ptr(), path.as_ptr());
-            check(err)?;
+            check(err, "save")?;
         }
 
         Ok(())
     }
 
-    pub fn load<P>(path: P) -> Result<Self>
+    /// Load a Matrix Market file. The block sizes in the options are not
+    /// used by librsb here.
+    ///
+    /// If neither the storage format nor the flags choose the kind of leaves,
+    /// the matrix is loaded with [Flags::DEFAULT_STORAGE] as librsb recommends.
+    pub fn load<P, O>(path: P, options: O) -> Result<Self>
     where
         P: AsRef<Path>,
+        O: Into<MtxOptions>,
     {
         let path = path.as_ref();
         let path = utils::try_osstr_to_cstr(path.as_os_str())
             .map_err(|err| Error::custom(format!("{}", err)))?;
-        let flags = storage::DEFAULT_STORAGE_FLAGS;
+        let mut options = options.into();
+        let storage_flags = Flags::WANT_COO_STORAGE | Flags::WANT_BCSS_STORAGE;
+        if options.storage == StorageFormat::Auto && !options.flags.intersects(storage_flags) {
+            options.storage = StorageFormat::Default;
+        }
+        let flags = options.to_flags();
 
         unsafe {
             let mut errval = RSB_ERR_NO_ERROR;
-            let ptr =
-                sys::rsb_file_mtx_load(path.as_ptr(), flags, T::TYPE_CODE, &mut errval as *mut _);
-            check(errval)?;
+            let ptr = sys::rsb_file_mtx_load(
+                path.as_ptr(),
+                flags.code(),
+                T::TYPE_CODE,
+                &mut errval as *mut _,
+            );
+            check(errval, "load")?;
 
             Ok(Self::from_raw(ptr))
         }
     }
 
-    fn ptr(&self) -> *const sys::rsb_mtx_t {
+    fn check_square(&self, operation: &str) -> Result<()> {
+        let [nr, nc] = self.dims()?;
+        ensure!(
+            nr == nc,
+            "{} requires a square matrix, but the matrix is {}x{}",
+            operation,
+            nr,
+            nc
+        );
+        Ok(())
+    }
+
+    fn check_spmv_shapes(
+        &self,
+        transpose: &Transpose,
+        rhs: &repr::Vector<'_, T>,
+        output: &repr::VectorMut<'_, T>,
+    ) -> Result<()> {
+        let [op_nr, op_nc] = transposed_dims(transpose, self.dims()?);
+        ensure!(
+            rhs.len() == op_nc,
+            "rhs has {} elements (stride {}), but the (transposed) matrix has {} columns",
+            rhs.len(),
+            rhs.stride()?,
+            op_nc
+        );
+        ensure!(
+            output.len() == op_nr,
+            "output has {} elements (stride {}), but the (transposed) matrix has {} rows",
+            output.len(),
+            output.stride()?,
+            op_nr
+        );
+
+        Ok(())
+    }
+
+    fn check_spmm_shapes(
+        &self,
+        transpose: &Transpose,
+        rhs: &repr::Matrix<'_, T>,
+        output: &repr::MatrixMut<'_, T>,
+    ) -> Result<()> {
+        ensure!(
+            rhs.order() == output.order(),
+            "rhs and output matrix major order must be the same"
+        );
+
+        let [op_nr, op_nc] = transposed_dims(transpose, self.dims()?);
+        let [rhs_nr, rhs_nc] = rhs.hw();
+        let [out_nr, out_nc] = output.hw();
+        ensure!(
+            rhs_nr == op_nc,
+            "rhs has {} rows, but the (transposed) matrix has {} columns",
+            rhs_nr,
+            op_nc
+        );
+        ensure!(
+            out_nr == op_nr,
+            "output has {} rows, but the (transposed) matrix has {} rows",
+            out_nr,
+            op_nr
+        );
+        ensure!(
+            rhs_nc == out_nc,
+            "rhs has {} columns, but output has {} columns",
+            rhs_nc,
+            out_nc
+        );
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn tune(
+        &mut self,
+        operation: &'static str,
+        tune_fn: TuneFn,
+        mode: TuningMode,
+        options: &SpmmOptions,
+        alpha: Option<T>,
+        rhs: repr::Matrix<'_, T>,
+        beta: Option<T>,
+        mut output: repr::MatrixMut<'_, T>,
+    ) -> Result<TuningReport> {
+        let alpha_ptr = alpha
+            .as_ref()
+            .map(|v| v as *const T)
+            .unwrap_or_else(|| ptr::null());
+        let beta_ptr = beta
+            .as_ref()
+            .map(|v| v as *const T)
+            .unwrap_or_else(|| ptr::null());
+
+        let mut speedup: sys::rsb_real_t = 0.0;
+        let mut num_threads = to_int(options.num_threads.unwrap_or(0))?;
+
+        // In-place tuning hands the matrix over through mtxOpp and may
+        // replace it, while thread-only tuning reads it through mtxAp.
+        let mut mtx_ptr = self.ptr_mut();
+        let (mtx_opp, mtx_ap) = match mode {
+            TuningMode::InPlace => (&mut mtx_ptr as *mut _, ptr::null()),
+            TuningMode::ThreadCountOnly => (ptr::null_mut(), self.ptr()),
+        };
+
+        let since = Instant::now();
+        let err = unsafe {
+            tune_fn(
+                mtx_opp,
+                &mut speedup as *mut _,
+                &mut num_threads as *mut _,
+                to_int(options.max_rounds)?,
+                options.limit.to_value(),
+                options.transpose.code(),
+                alpha_ptr as *const c_void,
+                mtx_ap,
+                rhs.num_vecs()?,
+                rhs.order().code(),
+                rhs.to_ptr(),
+                rhs.leading_dimension()?,
+                beta_ptr as *const c_void,
+                output.to_ptr(),
+                output.leading_dimension()?,
+            )
+        };
+        let elapsed = since.elapsed();
+
+        if let (TuningMode::InPlace, Some(ptr)) = (mode, NonNull::new(mtx_ptr)) {
+            // A reassembled matrix lives in librsb memory and no longer uses
+            // the arrays it was built in, so into_coo and into_csr must copy.
+            if Some(ptr) != self.ptr {
+                self.arrays = None;
+            }
+            self.ptr = Some(ptr);
+        }
+        check(err, operation).map_err(|err| {
+            self.error_with_shape(err, "matrix")
+                .with_shape("rhs", rhs.hw())
+                .with_shape("output", output.hw())
+        })?;
+
+        Ok(TuningReport {
+            speedup,
+            num_threads: num_threads as usize,
+            elapsed,
+        })
+    }
+
+    /// Switch a matrix assembled in place back to plain arrays and return the
+    /// vecs holding them.
+    fn switch_to(
+        mut self,
+        operation: &'static str,
+        switch_fn: SwitchFn,
+        index_base: IndexBase,
+    ) -> Result<InplaceArrays<T>> {
+        let arrays = self.arrays.take().unwrap();
+        let ptr = self.into_raw();
+        let mut va: *mut c_void = ptr::null_mut();
+        let mut ia: *mut sys::rsb_coo_idx_t = ptr::null_mut();
+        let mut ja: *mut sys::rsb_coo_idx_t = ptr::null_mut();
+
+        let err = unsafe {
+            switch_fn(
+                ptr,
+                &mut va as *mut _,
+                &mut ia as *mut _,
+                &mut ja as *mut _,
+                index_base.code(),
+            )
+        };
+
+        // Free whatever librsb handed back that is not one of our arrays.
+        let owned = arrays.as_ptrs();
+        let returned = [va, ia as *mut c_void, ja as *mut c_void];
+        let mut foreign = false;
+        for ret in returned {
+            if !ret.is_null() && !owned.contains(&(ret as *const c_void)) {
+                foreign = true;
+                unsafe {
+                    libc::free(ret);
+                }
+            }
+        }
+
+        if let Err(error) = check(err, operation) {
+            // The matrix is left intact on failure and must be freed before
+            // the arrays it uses.
+            drop(unsafe { Self::from_raw(ptr) });
+            drop(arrays);
+            return Err(error);
+        }
+
+        // The matrix structure is freed by librsb at this point, and the
+        // arrays it was assembled in are handed back.
+        ensure!(
+            !foreign
+                && returned
+                    .iter()
+                    .zip(&owned)
+                    .all(|(&ret, &own)| ptr::eq(ret, own)),
+            "{} got arrays other than the ones the matrix was assembled in",
+            operation
+        );
+
+        Ok(arrays)
+    }
+
+    fn get_vec(
+        &self,
+        operation: &'static str,
+        flag: sys::rsb_extff_t,
+        len: usize,
+        mut output: repr::VectorMut<'_, T>,
+    ) -> Result<()> {
+        ensure!(
+            output.len() == len,
+            "the output vector has {} elements, but {} are expected",
+            output.len(),
+            len
+        );
+
+        // librsb writes contiguous vectors only.
+        if output.stride()? == 1 {
+            unsafe {
+                let err = sys::rsb_mtx_get_vec(self.ptr(), output.to_ptr(), flag);
+                check(err, operation)?;
+            }
+        } else {
+            let mut buf = vec![T::zero(); len];
+            unsafe {
+                let err = sys::rsb_mtx_get_vec(self.ptr(), buf.as_mut_ptr() as *mut c_void, flag);
+                check(err, operation)?;
+            }
+            output.iter_mut().zip(buf).for_each(|(dst, src)| *dst = src);
+        }
+
+        Ok(())
+    }
+
+    fn check_scale_len(&self, len: usize, expect: usize, dim: &str) -> Result<()> {
+        ensure!(
+            len == expect,
+            "expect {} {} scales, but get {}",
+            expect,
+            dim,
+            len
+        );
+        Ok(())
+    }
+
+    /// Attach the matrix shape to an error if the shape is available.
+    fn error_with_shape(&self, err: Error, name: &'static str) -> Error {
+        match self.dims() {
+            Ok(hw) => err.with_shape(name, hw),
+            Err(_) => err,
+        }
+    }
+
+    fn dims(&self) -> Result<[usize; 2]> {
+        Ok([self.nrows()?, self.ncols()?])
+    }
+
+    unsafe fn get_info<V>(&self, flag: sys::rsb_mif_t, mut value: V) -> Result<V> {
+        let err = sys::rsb_mtx_get_info(self.ptr(), flag, &mut value as *mut V as *mut c_void);
+        check(err, "get_info")?;
+        Ok(value)
+    }
+
+    pub(crate) fn ptr(&self) -> *const sys::rsb_mtx_t {
         self.ptr.unwrap().as_ptr()
     }
 
@@ -554,6 +1962,54 @@ where
     }
 }
 
+/// Check that compressed row or column pointers start at the index base, do
+/// not decrease, and end at the index base plus `nnz`.
+fn check_pointers(
+    pointers: &[sys::rsb_nnz_idx_t],
+    nnz: usize,
+    index_base: IndexBase,
+    name: &str,
+) -> Result<()> {
+    let base = index_base.offset();
+    let end = to_nnz_idx(nnz)?
+        .checked_add(base)
+        .ok_or_else(|| Error::custom(format!("{} nonzeros overflow the {} pointers", nnz, name)))?;
+
+    ensure!(
+        pointers.first() == Some(&base),
+        "the {} pointers must start at {}, but get {:?}",
+        name,
+        base,
+        pointers.first()
+    );
+    if let Some(pos) = pointers.windows(2).position(|pair| pair[0] > pair[1]) {
+        return Err(Error::custom(format!(
+            "the {} pointers decrease from {} to {} at position {}",
+            name,
+            pointers[pos],
+            pointers[pos + 1],
+            pos
+        )));
+    }
+    ensure!(
+        pointers.last() == Some(&end),
+        "the {} pointers must end at {} for {} nonzeros, but get {:?}",
+        name,
+        end,
+        nnz,
+        pointers.last()
+    );
+    Ok(())
+}
+
+/// Get the shape of a matrix after applying the transpose.
+fn transposed_dims(transpose: &Transpose, [nr, nc]: [usize; 2]) -> [usize; 2] {
+    match transpose {
+        Transpose::None => [nr, nc],
+        Transpose::Transpose | Transpose::ConjugateTranspose => [nc, nr],
+    }
+}
+
 impl<T> Clone for Mtx<T>
 where
     T: NumericalType,
@@ -563,16 +2019,77 @@ where
     }
 }
 
+impl<T> fmt::Debug for Mtx<T>
+where
+    T: NumericalType,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Mtx");
+
+        match (self.info(), self.info_string()) {
+            (Ok(info), Ok(text)) => debug
+                .field("nrows", &info.nrows)
+                .field("ncols", &info.ncols)
+                .field("nnz", &info.nnz)
+                .field("symmetry", &Symmetry::from_flags(info.flags))
+                .field("type", &numerical_type::type_name(info.type_code))
+                .field("info", &text),
+            _ => debug.field("ptr", &self.ptr),
+        }
+        .finish()
+    }
+}
+
+impl<T> Display for Mtx<T>
+where
+    T: NumericalType + Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Fall back to the raw pointer like Debug, since returning an error
+        // would make `to_string()` panic.
+        let (info, text) = match (self.info(), self.info_string()) {
+            (Ok(info), Ok(text)) => (info, text),
+            _ => return write!(f, "matrix at {:?}", self.ptr),
+        };
+
+        write!(
+            f,
+            "{}x{} {} matrix, nnz = {}, symmetry = {:?}: {}",
+            info.nrows,
+            info.ncols,
+            numerical_type::type_name(info.type_code),
+            info.nnz,
+            Symmetry::from_flags(info.flags),
+            text
+        )?;
+
+        if f.alternate() && info.nrows <= PREVIEW_MAX_DIM && info.ncols <= PREVIEW_MAX_DIM {
+            let (rows, cols): (Vec<_>, Vec<_>) = (0..info.nrows)
+                .flat_map(|row| (0..info.ncols).map(move |col| (row, col)))
+                .unzip();
+            let mut values: Vec<T> = rows.iter().map(|_| T::zero()).collect();
+            let preview = self.get_by_coo_slices(&mut values, &rows, &cols, IndexBase::Zero);
+
+            // The preview is omitted if the values cannot be read.
+            if preview.is_ok() && info.ncols > 0 {
+                for row in values.chunks(info.ncols) {
+                    writeln!(f)?;
+                    write!(f, "[{}]", row.iter().join(", "))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl<T> Extend<(usize, usize, T)> for Mtx<T>
 where
     T: NumericalType,
 {
     fn extend<I: IntoIterator<Item = (usize, usize, T)>>(&mut self, iter: I) {
-        let (rows, cols, vals) = iter
-            .into_iter()
-            .map(|(row, col, val)| (row as sys::rsb_coo_idx_t, col as sys::rsb_coo_idx_t, val))
-            .unzip_n_vec();
-        self.extend_by_coo_slices(&rows, &cols, &vals, Default::default())
+        let (rows, cols, vals): (Vec<usize>, _, _) = iter.into_iter().unzip_n_vec();
+        self.extend_by_coo_slices(&rows, &cols, &vals, Default::default(), IndexBase::Zero)
             .unwrap();
     }
 }
@@ -596,3 +2113,333 @@ where
 //     pub cols: usize,
 //     pub nonzeros: usize,
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A = [[1, 0, 2], [0, 3, 0]]
+    fn sample() -> Mtx<f64> {
+        Mtx::try_from_coo_slices(
+            2,
+            3,
+            &[1.0, 2.0, 3.0],
+            &[0, 0, 1],
+            &[0, 2, 1],
+            Symmetry::General,
+        )
+        .unwrap()
+    }
+
+    /// The sample matrix assembled in place inside COO vecs.
+    fn sample_coo_vecs() -> Mtx<f64> {
+        Mtx::try_from_coo_vecs(
+            2,
+            3,
+            vec![1.0, 2.0, 3.0],
+            vec![0, 0, 1],
+            vec![0, 2, 1],
+            Symmetry::General,
+        )
+        .unwrap()
+    }
+
+    /// The sample matrix assembled in place inside CSR vecs.
+    fn sample_csr_vecs() -> Mtx<f64> {
+        Mtx::try_from_csr_vecs(
+            2,
+            3,
+            vec![1.0, 2.0, 3.0],
+            vec![0, 2, 3],
+            vec![0, 2, 1],
+            Symmetry::General,
+        )
+        .unwrap()
+    }
+
+    fn check_sample(mtx: &Mtx<f64>) {
+        assert_eq!(mtx.dims().unwrap(), [2, 3]);
+        assert_eq!(mtx.nnz().unwrap(), 3);
+        assert_eq!(
+            mtx.to_dense(MajorOrder::Row).unwrap(),
+            [1.0, 0.0, 2.0, 0.0, 3.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn coo_vecs_build_in_place() {
+        let mtx = sample_coo_vecs();
+        assert!(mtx.arrays.is_some());
+        check_sample(&mtx);
+    }
+
+    #[test]
+    fn csr_vecs_build_in_place() {
+        let mtx = sample_csr_vecs();
+        match &mtx.arrays {
+            // The row pointers must be able to hold one row index per nonzero.
+            Some(InplaceArrays::Csr { rp, .. }) => assert!(rp.len() >= 3),
+            _ => panic!("the matrix does not own its CSR arrays"),
+        }
+        check_sample(&mtx);
+    }
+
+    type Entries = Vec<((sys::rsb_coo_idx_t, sys::rsb_coo_idx_t), f64)>;
+
+    /// The sample nonzeros sorted by position.
+    fn sample_entries() -> Entries {
+        vec![((0, 0), 1.0), ((0, 2), 2.0), ((1, 1), 3.0)]
+    }
+
+    fn coo_entries(
+        (rows, cols, vals): (Vec<sys::rsb_coo_idx_t>, Vec<sys::rsb_coo_idx_t>, Vec<f64>),
+    ) -> Entries {
+        assert_eq!(rows.len(), vals.len());
+        assert_eq!(cols.len(), vals.len());
+        let mut entries: Entries = rows.into_iter().zip(cols).zip(vals).collect();
+        entries.sort_by_key(|&(pos, _)| pos);
+        entries
+    }
+
+    fn csr_entries(
+        (row_ptrs, cols, vals): (Vec<sys::rsb_nnz_idx_t>, Vec<sys::rsb_coo_idx_t>, Vec<f64>),
+    ) -> Entries {
+        assert_eq!(row_ptrs, [0, 2, 3]);
+        assert_eq!(cols.len(), vals.len());
+        let rows = row_ptrs
+            .windows(2)
+            .enumerate()
+            .flat_map(|(row, pair)| (pair[0]..pair[1]).map(move |_| row as sys::rsb_coo_idx_t))
+            .collect();
+        coo_entries((rows, cols, vals))
+    }
+
+    #[test]
+    fn coo_vecs_into_coo() {
+        let coo = sample_coo_vecs().into_coo(IndexBase::Zero).unwrap();
+        assert_eq!(coo_entries(coo), sample_entries());
+    }
+
+    #[test]
+    fn coo_vecs_into_csr() {
+        let csr = sample_coo_vecs().into_csr(IndexBase::Zero).unwrap();
+        assert_eq!(csr_entries(csr), sample_entries());
+    }
+
+    #[test]
+    fn csr_vecs_into_csr() {
+        let csr = sample_csr_vecs().into_csr(IndexBase::Zero).unwrap();
+        assert_eq!(csr_entries(csr), sample_entries());
+    }
+
+    #[test]
+    fn csr_vecs_into_coo() {
+        let coo = sample_csr_vecs().into_coo(IndexBase::Zero).unwrap();
+        assert_eq!(coo_entries(coo), sample_entries());
+    }
+
+    #[test]
+    fn into_copies_other_matrices() {
+        let mtx = sample();
+        assert!(mtx.arrays.is_none());
+        assert_eq!(
+            coo_entries(mtx.into_coo(IndexBase::Zero).unwrap()),
+            sample_entries()
+        );
+        assert_eq!(
+            csr_entries(sample().into_csr(IndexBase::Zero).unwrap()),
+            sample_entries()
+        );
+
+        // The row index vec cannot hold the row pointers of a taller matrix.
+        let mtx = Mtx::try_from_coo_vecs(
+            4,
+            3,
+            vec![1.0, 2.0, 3.0],
+            vec![0, 0, 1],
+            vec![0, 2, 1],
+            Symmetry::General,
+        )
+        .unwrap();
+        let (row_ptrs, cols, vals) = mtx.into_csr(IndexBase::Zero).unwrap();
+        assert_eq!(row_ptrs, [0, 2, 3, 3, 3]);
+        assert_eq!(cols.len(), 3);
+        assert_eq!(vals.len(), 3);
+    }
+
+    #[test]
+    fn spmm_row_major_non_square() {
+        let mtx = sample();
+        let rhs = [
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 10.0, 11.0, 12.0],
+        ];
+        let mut output = [[0.0; 4]; 2];
+
+        mtx.spmm(
+            &SpmmOptions::default(),
+            1.0,
+            repr::Matrix::from_row_array(&rhs),
+            0.0,
+            repr::MatrixMut::from_row_array(&mut output),
+        )
+        .unwrap();
+
+        assert_eq!(output, [[19.0, 22.0, 25.0, 28.0], [15.0, 18.0, 21.0, 24.0]]);
+    }
+
+    #[test]
+    fn spmm_col_major_non_square() {
+        let mtx = sample();
+        // The columns of the 3x4 matrix in the row major test.
+        let rhs = [
+            [1.0, 5.0, 9.0],
+            [2.0, 6.0, 10.0],
+            [3.0, 7.0, 11.0],
+            [4.0, 8.0, 12.0],
+        ];
+        let mut output = [[0.0; 2]; 4];
+
+        mtx.spmm(
+            &SpmmOptions::default(),
+            1.0,
+            repr::Matrix::from_col_array(&rhs),
+            0.0,
+            repr::MatrixMut::from_col_array(&mut output),
+        )
+        .unwrap();
+
+        assert_eq!(
+            output,
+            [[19.0, 15.0], [22.0, 18.0], [25.0, 21.0], [28.0, 24.0]]
+        );
+    }
+
+    #[test]
+    fn iter_yields_all_nonzeros() {
+        let mut entries: Vec<_> = sample().iter().unwrap().collect::<Result<_>>().unwrap();
+        entries.sort_by_key(|&(row, col, _)| (row, col));
+        assert_eq!(entries, [(0, 0, 1.0), (0, 2, 2.0), (1, 1, 3.0)]);
+    }
+
+    #[test]
+    fn block_coo_expands_symmetry() {
+        // A = [[4, 1, 0], [1, 0, 2], [0, 2, 5]] with the lower triangle stored.
+        let mtx = Mtx::try_from_coo_slices(
+            3,
+            3,
+            &[4.0, 1.0, 2.0, 5.0],
+            &[0, 1, 2, 2],
+            &[0, 0, 1, 2],
+            Symmetry::LowerSymmetric,
+        )
+        .unwrap();
+
+        let (ia, ja, va) = mtx.block_coo(0..2, 1..3).unwrap();
+        let mut entries: Vec<_> = ia.into_iter().zip(ja).zip(va).collect();
+        entries.sort_by_key(|&(pos, _)| pos);
+        assert_eq!(entries, [((0, 0), 1.0), ((1, 1), 2.0)]);
+
+        let block = mtx.block(0..2, 0..2).unwrap();
+        assert_eq!(block.get(0, 1).unwrap(), 1.0);
+        assert_eq!(block.get(1, 0).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn load_keeps_storage_flags() {
+        let path = std::env::temp_dir().join(format!("rsb-load-{}.mtx", std::process::id()));
+        std::fs::write(
+            &path,
+            "%%MatrixMarket matrix coordinate real general\n2 3 3\n1 1 1\n1 3 2\n2 2 3\n",
+        )
+        .unwrap();
+
+        let coo = Mtx::<f64>::load(&path, Flags::DEFAULT_COO_MATRIX);
+        let default = Mtx::<f64>::load(&path, MtxOptions::default());
+        std::fs::remove_file(&path).unwrap();
+
+        let flags = coo.unwrap().flags().unwrap();
+        assert!(flags.contains(Flags::WANT_COO_STORAGE));
+        assert!(!flags.contains(Flags::WANT_BCSS_STORAGE));
+
+        let flags = default.unwrap().flags().unwrap();
+        assert!(flags.contains(Flags::DEFAULT_STORAGE));
+    }
+
+    #[test]
+    fn usize_indices() {
+        let mut mtx = Mtx::try_from_coo_slices(
+            2,
+            3,
+            &[1.0, 2.0, 3.0],
+            &[0usize, 0, 1],
+            &[0usize, 2, 1],
+            Symmetry::General,
+        )
+        .unwrap();
+        mtx.extend_by_coo_slices(
+            &[0usize],
+            &[2usize],
+            &[4.0],
+            Duplicates::Sum,
+            IndexBase::Zero,
+        )
+        .unwrap();
+
+        let mut values = [0.0; 2];
+        mtx.get_by_coo_slices(&mut values, &[0usize, 1], &[2usize, 1], IndexBase::Zero)
+            .unwrap();
+        assert_eq!(values, [6.0, 3.0]);
+    }
+
+    #[test]
+    fn slice_lengths_are_checked() {
+        let va = [1.0, 2.0, 3.0];
+        assert!(
+            Mtx::try_from_coo_slices(2, 3, &va, &[0, 0], &[0, 2, 1], Symmetry::General).is_err()
+        );
+        assert!(
+            Mtx::try_from_csr_slices(2, 3, &va, &[0, 2], &[0, 2, 1], Symmetry::General).is_err()
+        );
+        assert!(
+            Mtx::try_from_csc_slices(2, 3, &va, &[0, 0, 1], &[0, 1, 2], Symmetry::General).is_err()
+        );
+
+        let mtx =
+            Mtx::try_from_csr_slices(2, 3, &va, &[0, 2, 3], &[0, 2, 1], Symmetry::General).unwrap();
+        assert_eq!(mtx.nnz().unwrap(), 3);
+    }
+
+    #[test]
+    fn pointer_contents_are_checked() {
+        let va = [1.0, 2.0, 3.0];
+        let ja = [0, 2, 1];
+        for rp in [[1, 2, 3], [0, 2, 4], [0, 2, 2], [0, 3, 2]] {
+            assert!(Mtx::try_from_csr_slices(2, 3, &va, &rp, &ja, Symmetry::General).is_err());
+            assert!(Mtx::try_from_csr_vecs(
+                2,
+                3,
+                va.to_vec(),
+                rp.to_vec(),
+                ja.to_vec(),
+                Symmetry::General
+            )
+            .is_err());
+        }
+        assert!(
+            Mtx::try_from_csc_slices(2, 3, &va, &[0, 1, 0], &[0, 1, 2, 4], Symmetry::General)
+                .is_err()
+        );
+
+        // 1-based pointers start at one.
+        let ja = [1, 3, 2];
+        let one = MtxOptions {
+            index_base: IndexBase::One,
+            ..Default::default()
+        };
+        assert!(Mtx::try_from_csr_slices(2, 3, &va, &[0, 2, 3], &ja, one.clone()).is_err());
+        let mtx = Mtx::try_from_csr_slices(2, 3, &va, &[1, 3, 4], &ja, one).unwrap();
+        assert_eq!(mtx.get(0, 2).unwrap(), 2.0);
+    }
+}
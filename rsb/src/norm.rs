@@ -0,0 +1,21 @@
+use crate::common::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Norm {
+    /// The maximum absolute column sum.
+    One,
+    /// The maximum absolute row sum.
+    Infinity,
+    /// The square root of the sum of squared absolute values.
+    Frobenius,
+}
+
+impl Norm {
+    pub fn code(&self) -> sys::rsb_extff_t {
+        match self {
+            Self::One => sys::rsb_extff_t::RSB_EXTF_NORM_ONE,
+            Self::Infinity => sys::rsb_extff_t::RSB_EXTF_NORM_INF,
+            Self::Frobenius => sys::rsb_extff_t::RSB_EXTF_NORM_TWO,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::common::*;
+use num_complex::Complex;
 
 const DOUBLE: sys::rsb_type_t = sys::rsb_type_t(sys::RSB_NUMERICAL_TYPE_DOUBLE as c_char);
 const FLOAT: sys::rsb_type_t = sys::rsb_type_t(sys::RSB_NUMERICAL_TYPE_FLOAT as c_char);
@@ -7,24 +8,100 @@ const DOUBLE_COMPLEX: sys::rsb_type_t =
 const FLOAT_COMPLEX: sys::rsb_type_t =
     sys::rsb_type_t(sys::RSB_NUMERICAL_TYPE_FLOAT_COMPLEX as c_char);
 
-pub trait NumericalType {
+/// Get the human-readable name of a librsb numerical type code.
+pub fn type_name(code: sys::rsb_type_t) -> &'static str {
+    match code {
+        DOUBLE => "double",
+        FLOAT => "float",
+        DOUBLE_COMPLEX => "double complex",
+        FLOAT_COMPLEX => "float complex",
+        _ => "unknown",
+    }
+}
+
+pub trait NumericalType: Copy {
     const TYPE_CODE: sys::rsb_type_t;
 
+    /// The real type with the same precision.
+    type Real: NumericalType;
+
     fn zero() -> Self;
+
+    /// The real part of the value.
+    fn re(&self) -> Self::Real;
+
+    /// The complex conjugate, or the value itself for real types.
+    fn conj(&self) -> Self;
 }
 
 impl NumericalType for f64 {
     const TYPE_CODE: sys::rsb_type_t = DOUBLE;
 
+    type Real = f64;
+
     fn zero() -> Self {
         0.0
     }
+
+    fn re(&self) -> Self::Real {
+        *self
+    }
+
+    fn conj(&self) -> Self {
+        *self
+    }
 }
 
 impl NumericalType for f32 {
     const TYPE_CODE: sys::rsb_type_t = FLOAT;
 
+    type Real = f32;
+
     fn zero() -> Self {
         0.0
     }
+
+    fn re(&self) -> Self::Real {
+        *self
+    }
+
+    fn conj(&self) -> Self {
+        *self
+    }
+}
+
+impl NumericalType for Complex<f64> {
+    const TYPE_CODE: sys::rsb_type_t = DOUBLE_COMPLEX;
+
+    type Real = f64;
+
+    fn zero() -> Self {
+        Complex::new(0.0, 0.0)
+    }
+
+    fn re(&self) -> Self::Real {
+        self.re
+    }
+
+    fn conj(&self) -> Self {
+        Complex::conj(self)
+    }
+}
+
+impl NumericalType for Complex<f32> {
+    const TYPE_CODE: sys::rsb_type_t = FLOAT_COMPLEX;
+
+    type Real = f32;
+
+    fn zero() -> Self {
+        Complex::new(0.0, 0.0)
+    }
+
+    fn re(&self) -> Self::Real {
+        self.re
+    }
+
+    fn conj(&self) -> Self {
+        Complex::conj(self)
+    }
 }
@@ -0,0 +1,103 @@
+use crate::{
+    common::*,
+    discard_zero::DiscardZero,
+    duplicates::Duplicates,
+    error::Result,
+    flag::Flags,
+    index_interface::{to_coo_idx, IndexBase},
+    storage::StorageFormat,
+    symmetry::Symmetry,
+};
+
+/// The options to assemble a [Mtx](crate::mtx::Mtx).
+///
+/// Any of the typed options or [Flags] converts into `MtxOptions` with the
+/// other fields left to their defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MtxOptions {
+    /// The block rows of the leaves. `None` lets librsb decide.
+    pub block_rows: Option<usize>,
+    /// The block columns of the leaves. `None` lets librsb decide.
+    pub block_cols: Option<usize>,
+    pub storage: StorageFormat,
+    pub symmetry: Symmetry,
+    pub duplicates: Duplicates,
+    pub discard_zero: DiscardZero,
+    /// The input is already sorted by row, then by column.
+    pub sorted_input: bool,
+    /// The diagonal is implicitly one and not stored.
+    pub unit_diagonal: bool,
+    pub index_base: IndexBase,
+    /// Additional flags passed to librsb as is.
+    pub flags: Flags,
+}
+
+impl MtxOptions {
+    /// Combine the options into librsb flags.
+    pub fn to_flags(&self) -> Flags {
+        let mut flags = self.flags;
+        flags |= self.storage;
+        flags |= self.symmetry.clone();
+        flags |= self.duplicates;
+        flags |= self.discard_zero;
+        flags |= self.index_base;
+        if self.sorted_input {
+            flags |= Flags::SORTED_INPUT;
+        }
+        if self.unit_diagonal {
+            flags |= Flags::UNIT_DIAG_IMPLICIT;
+        }
+        flags
+    }
+
+    pub(crate) fn blocking(&self) -> Result<(sys::rsb_blk_idx_t, sys::rsb_blk_idx_t)> {
+        let to_value = |size: Option<usize>| match size {
+            Some(size) => to_coo_idx(size),
+            None => Ok(sys::RSB_DEFAULT_BLOCKING as sys::rsb_blk_idx_t),
+        };
+        Ok((to_value(self.block_rows)?, to_value(self.block_cols)?))
+    }
+}
+
+impl Default for MtxOptions {
+    fn default() -> Self {
+        Self {
+            block_rows: None,
+            block_cols: None,
+            storage: Default::default(),
+            symmetry: Symmetry::General,
+            duplicates: Default::default(),
+            discard_zero: false.into(),
+            sorted_input: false,
+            unit_diagonal: false,
+            index_base: Default::default(),
+            flags: Flags::empty(),
+        }
+    }
+}
+
+impl From<&MtxOptions> for MtxOptions {
+    fn from(options: &MtxOptions) -> Self {
+        options.clone()
+    }
+}
+
+macro_rules! impl_from_field {
+    ($ty:ty, $field:ident) => {
+        impl From<$ty> for MtxOptions {
+            fn from($field: $ty) -> Self {
+                Self {
+                    $field,
+                    ..Default::default()
+                }
+            }
+        }
+    };
+}
+
+impl_from_field!(Flags, flags);
+impl_from_field!(StorageFormat, storage);
+impl_from_field!(Symmetry, symmetry);
+impl_from_field!(Duplicates, duplicates);
+impl_from_field!(DiscardZero, discard_zero);
+impl_from_field!(IndexBase, index_base);
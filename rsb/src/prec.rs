@@ -0,0 +1,112 @@
+use crate::{
+    common::*,
+    error::{check, ensure, Error, Result},
+    mtx::Mtx,
+    numerical_type::NumericalType,
+    repr,
+    transpose::Transpose,
+};
+
+/// The incomplete LU factorization with zero fill-in, `A ≈ LU`.
+#[derive(Debug, Clone)]
+pub struct Ilu0<T>
+where
+    T: NumericalType,
+{
+    l: Mtx<T>,
+    u: Mtx<T>,
+}
+
+impl<T> Ilu0<T>
+where
+    T: NumericalType + PartialEq,
+{
+    /// Compute the ILU(0) factors of a square matrix.
+    pub fn new(mtx: &Mtx<T>) -> Result<Self> {
+        let nr = mtx.nrows()?;
+        let nc = mtx.ncols()?;
+        ensure!(
+            nr == nc,
+            "ILU(0) requires a square matrix, but the matrix is {}x{}",
+            nr,
+            nc
+        );
+
+        let mut factors: [*mut sys::rsb_mtx_t; 2] = [ptr::null_mut(); 2];
+
+        let err = unsafe {
+            sys::rsb_mtx_get_prec(
+                factors.as_mut_ptr() as *mut c_void,
+                mtx.ptr(),
+                sys::RSB_PRECF_ILU0 as sys::rsb_precf_t,
+                ptr::null(),
+            )
+        };
+
+        // Take ownership of any factor librsb allocated, so that it is freed
+        // if the factorization failed.
+        let [l, u] = factors
+            .map(|factor| (!factor.is_null()).then(|| unsafe { Mtx::<T>::from_raw(factor) }));
+        check(err, "ilu0")?;
+        let (l, u) = match (l, u) {
+            (Some(l), Some(u)) => (l, u),
+            _ => {
+                return Err(Error::custom(
+                    "ILU(0) factorization did not produce the factors",
+                ))
+            }
+        };
+
+        // The diagonal of U holds the pivots.
+        let pivots = u.diagonal()?;
+        if let Some(row) = pivots.iter().position(|pivot| *pivot == T::zero()) {
+            return Err(Error::custom(format!(
+                "ILU(0) factorization encountered a zero pivot at row {}",
+                row
+            )));
+        }
+
+        Ok(Self { l, u })
+    }
+}
+
+impl<T> Ilu0<T>
+where
+    T: NumericalType,
+{
+    /// Get the lower triangular factor.
+    pub fn l(&self) -> &Mtx<T> {
+        &self.l
+    }
+
+    /// Get the upper triangular factor.
+    pub fn u(&self) -> &Mtx<T> {
+        &self.u
+    }
+
+    /// Solve `LUx = rhs` by two triangular solves and store `x` in `output`.
+    pub fn apply<'a, R, O>(&self, rhs: R, output: O) -> Result<()>
+    where
+        R: Into<repr::Vector<'a, T>>,
+        O: Into<repr::VectorMut<'a, T>>,
+        T: 'a,
+    {
+        let rhs = rhs.into();
+        let output = output.into();
+        let n = self.l.nrows()?;
+        ensure!(
+            rhs.len() == n && output.len() == n,
+            "expect vectors of length {}, but get rhs of length {} and output of length {}",
+            n,
+            rhs.len(),
+            output.len()
+        );
+
+        let mut tmp = vec![T::zero(); n];
+        self.l
+            .spsv(Transpose::None, None, rhs, tmp.as_mut_slice())?;
+        self.u.spsv(Transpose::None, None, tmp.as_slice(), output)?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,51 @@
 use crate::{
     common::*,
     error::{ensure, Result},
+    index_interface::{to_coo_idx, to_nnz_idx},
     major_order::MajorOrder,
 };
 
+fn natural_leading_dimension(order: MajorOrder, hw: [usize; 2]) -> usize {
+    match order {
+        MajorOrder::Column => hw[0],
+        MajorOrder::Row => hw[1],
+    }
+}
+
+fn check_leading_dimension(order: MajorOrder, hw: [usize; 2], ld: usize, len: usize) -> Result<()> {
+    let [nrows, ncols] = hw;
+    let (inner, outer) = match order {
+        MajorOrder::Column => (nrows, ncols),
+        MajorOrder::Row => (ncols, nrows),
+    };
+    ensure!(
+        ld >= inner.max(1),
+        "the leading dimension {} is less than {} for the shape (rows, cols) = ({}, {}) in {:?} major order",
+        ld,
+        inner.max(1),
+        nrows,
+        ncols,
+        order
+    );
+
+    let required = if outer == 0 {
+        0
+    } else {
+        ld * (outer - 1) + inner
+    };
+    ensure!(
+        len >= required,
+        "the slice length {} is less than {} required by the shape (rows, cols) = ({}, {}) with leading dimension {}",
+        len,
+        required,
+        nrows,
+        ncols,
+        ld
+    );
+
+    Ok(())
+}
+
 pub use matrix::*;
 mod matrix {
     use super::*;
@@ -12,6 +54,7 @@ mod matrix {
     pub struct Matrix<'a, T> {
         pub(super) order: MajorOrder,
         pub(super) hw: [usize; 2],
+        pub(super) ld: usize,
         pub(super) slice: &'a [T],
     }
 
@@ -38,31 +81,64 @@ mod matrix {
                 slice.len()
             );
 
+            let ld = natural_leading_dimension(order, [nrows, ncols]);
+            Ok(Self {
+                order,
+                hw: [nrows, ncols],
+                ld,
+                slice,
+            })
+        }
+
+        /// Create a matrix whose consecutive rows (in row major order) or
+        /// columns (in column major order) are `ld` elements apart.
+        pub fn from_slice_with_ld(
+            nrows: usize,
+            ncols: usize,
+            order: MajorOrder,
+            ld: usize,
+            slice: &'a [T],
+        ) -> Result<Self> {
+            check_leading_dimension(order, [nrows, ncols], ld, slice.len())?;
+
             Ok(Self {
                 order,
                 hw: [nrows, ncols],
+                ld,
                 slice,
             })
         }
 
+        /// Create a matrix from nested arrays, where each inner array is a row
+        /// in row major order or a column in column major order.
+        ///
+        /// The shape is `[N1, N2]` in row major order and `[N2, N1]` in column
+        /// major order.
         pub fn from_array<const N1: usize, const N2: usize>(
             order: MajorOrder,
             array: &'a [[T; N2]; N1],
         ) -> Self {
             let slice = array.flat();
             let hw = match order {
-                MajorOrder::Column => [N1, N2],
-                MajorOrder::Row => [N2, N1],
+                MajorOrder::Column => [N2, N1],
+                MajorOrder::Row => [N1, N2],
             };
-            Self { order, hw, slice }
+            Self {
+                order,
+                hw,
+                ld: N2,
+                slice,
+            }
         }
 
-        pub fn from_col_array<const NR: usize, const NC: usize>(array: &'a [[T; NC]; NR]) -> Self {
+        /// Create an `NR` by `NC` matrix from `NC` columns.
+        pub fn from_col_array<const NR: usize, const NC: usize>(array: &'a [[T; NR]; NC]) -> Self {
             Self::from_array(MajorOrder::Column, array)
         }
 
-        pub fn from_row_array<const NR: usize, const NC: usize>(array: &'a [[T; NR]; NC]) -> Self {
-            Self::from_array(MajorOrder::Column, array)
+        /// Create an `NR` by `NC` matrix from `NR` rows.
+        pub fn from_row_array<const NR: usize, const NC: usize>(array: &'a [[T; NC]; NR]) -> Self {
+            Self::from_array(MajorOrder::Row, array)
         }
 
         /// Get the matrix repr's order.
@@ -84,20 +160,19 @@ mod matrix {
             self.hw
         }
 
-        pub(crate) fn leading_dimension(&self) -> sys::rsb_nnz_idx_t {
-            let value = match self.order {
-                MajorOrder::Column => self.hw[0],
-                MajorOrder::Row => self.hw[1],
-            };
-            value as sys::rsb_nnz_idx_t
+        /// Get the distance between consecutive rows or columns.
+        pub fn ld(&self) -> usize {
+            self.ld
         }
 
-        pub(crate) fn num_vecs(&self) -> sys::rsb_coo_idx_t {
-            let value = match self.order {
-                MajorOrder::Column => self.hw[1],
-                MajorOrder::Row => self.hw[0],
-            };
-            value as sys::rsb_coo_idx_t
+        pub(crate) fn leading_dimension(&self) -> Result<sys::rsb_nnz_idx_t> {
+            to_nnz_idx(self.ld)
+        }
+
+        /// Get the number of right-hand sides, which is the number of columns
+        /// in either major order.
+        pub(crate) fn num_vecs(&self) -> Result<sys::rsb_coo_idx_t> {
+            to_coo_idx(self.hw[1])
         }
     }
 }
@@ -110,6 +185,7 @@ mod matrix_mut {
     pub struct MatrixMut<'a, T> {
         pub(super) order: MajorOrder,
         pub(super) hw: [usize; 2],
+        pub(super) ld: usize,
         pub(super) slice: &'a mut [T],
     }
 
@@ -136,35 +212,68 @@ mod matrix_mut {
                 slice.len()
             );
 
+            let ld = natural_leading_dimension(order, [nrows, ncols]);
             Ok(Self {
                 order,
                 hw: [nrows, ncols],
+                ld,
                 slice,
             })
         }
 
+        /// Create a matrix whose consecutive rows (in row major order) or
+        /// columns (in column major order) are `ld` elements apart.
+        pub fn from_slice_with_ld(
+            nrows: usize,
+            ncols: usize,
+            order: MajorOrder,
+            ld: usize,
+            slice: &'a mut [T],
+        ) -> Result<Self> {
+            check_leading_dimension(order, [nrows, ncols], ld, slice.len())?;
+
+            Ok(Self {
+                order,
+                hw: [nrows, ncols],
+                ld,
+                slice,
+            })
+        }
+
+        /// Create a matrix from nested arrays, where each inner array is a row
+        /// in row major order or a column in column major order.
+        ///
+        /// The shape is `[N1, N2]` in row major order and `[N2, N1]` in column
+        /// major order.
         pub fn from_array<const N1: usize, const N2: usize>(
             order: MajorOrder,
             array: &'a mut [[T; N2]; N1],
         ) -> Self {
             let slice = array.flat_mut();
             let hw = match order {
-                MajorOrder::Column => [N1, N2],
-                MajorOrder::Row => [N2, N1],
+                MajorOrder::Column => [N2, N1],
+                MajorOrder::Row => [N1, N2],
             };
-            Self { order, hw, slice }
+            Self {
+                order,
+                hw,
+                ld: N2,
+                slice,
+            }
         }
 
+        /// Create an `NR` by `NC` matrix from `NC` columns.
         pub fn from_col_array<const NR: usize, const NC: usize>(
-            array: &'a mut [[T; NC]; NR],
+            array: &'a mut [[T; NR]; NC],
         ) -> Self {
             Self::from_array(MajorOrder::Column, array)
         }
 
+        /// Create an `NR` by `NC` matrix from `NR` rows.
         pub fn from_row_array<const NR: usize, const NC: usize>(
-            array: &'a mut [[T; NR]; NC],
+            array: &'a mut [[T; NC]; NR],
         ) -> Self {
-            Self::from_array(MajorOrder::Column, array)
+            Self::from_array(MajorOrder::Row, array)
         }
 
         /// Get the matrix repr's order.
@@ -186,20 +295,19 @@ mod matrix_mut {
             self.hw
         }
 
-        pub(crate) fn leading_dimension(&self) -> sys::rsb_nnz_idx_t {
-            let value = match self.order {
-                MajorOrder::Column => self.hw[0],
-                MajorOrder::Row => self.hw[1],
-            };
-            value as sys::rsb_nnz_idx_t
+        /// Get the distance between consecutive rows or columns.
+        pub fn ld(&self) -> usize {
+            self.ld
         }
 
-        pub(crate) fn num_vecs(&self) -> sys::rsb_coo_idx_t {
-            let value = match self.order {
-                MajorOrder::Column => self.hw[1],
-                MajorOrder::Row => self.hw[0],
-            };
-            value as sys::rsb_coo_idx_t
+        pub(crate) fn leading_dimension(&self) -> Result<sys::rsb_nnz_idx_t> {
+            to_nnz_idx(self.ld)
+        }
+
+        /// Get the number of right-hand sides, which is the number of columns
+        /// in either major order.
+        pub(crate) fn num_vecs(&self) -> Result<sys::rsb_coo_idx_t> {
+            to_coo_idx(self.hw[1])
         }
     }
 }
@@ -223,9 +331,9 @@ mod vector {
             ensure!(stride >= 1, "stride must be positive, but get zero");
             ensure!(
                 slice.len() % stride == 0,
-                "stride {} is not multiple of slice length {}",
-                stride,
-                slice.len()
+                "the slice length {} is not a multiple of the stride {}",
+                slice.len(),
+                stride
             );
             Ok(Self { slice, stride })
         }
@@ -234,9 +342,23 @@ mod vector {
             self.slice.as_ptr() as *const c_void
         }
 
+        /// Get the number of elements in the vector.
+        pub fn len(&self) -> usize {
+            self.slice.len().div_ceil(self.stride)
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.slice.is_empty()
+        }
+
+        /// Iterate over the elements, skipping the gaps between strides.
+        pub fn iter(&self) -> impl Iterator<Item = &T> {
+            self.slice.iter().step_by(self.stride)
+        }
+
         /// Get the vector's stride.
-        pub(crate) fn stride(&self) -> sys::rsb_coo_idx_t {
-            self.stride as sys::rsb_coo_idx_t
+        pub(crate) fn stride(&self) -> Result<sys::rsb_coo_idx_t> {
+            to_coo_idx(self.stride)
         }
     }
 
@@ -266,9 +388,9 @@ mod vector_mut {
             ensure!(stride >= 1, "stride must be positive, but get zero");
             ensure!(
                 slice.len() % stride == 0,
-                "stride {} is not multiple of slice length {}",
-                stride,
-                slice.len()
+                "the slice length {} is not a multiple of the stride {}",
+                slice.len(),
+                stride
             );
             Ok(Self { slice, stride })
         }
@@ -277,9 +399,23 @@ mod vector_mut {
             self.slice.as_mut_ptr() as *mut c_void
         }
 
+        /// Get the number of elements in the vector.
+        pub fn len(&self) -> usize {
+            self.slice.len().div_ceil(self.stride)
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.slice.is_empty()
+        }
+
+        /// Iterate over the elements, skipping the gaps between strides.
+        pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+            self.slice.iter_mut().step_by(self.stride)
+        }
+
         /// Get the vector mut's stride.
-        pub(crate) fn stride(&self) -> sys::rsb_coo_idx_t {
-            self.stride as sys::rsb_coo_idx_t
+        pub(crate) fn stride(&self) -> Result<sys::rsb_coo_idx_t> {
+            to_coo_idx(self.stride)
         }
     }
 
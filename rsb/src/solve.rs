@@ -0,0 +1,855 @@
+//! Krylov iterative solvers for `Ax = b`.
+//!
+//! The solvers take any [Operator], including [Mtx](crate::mtx::Mtx), and an
+//! optional [Preconditioner] such as [Ilu0](crate::prec::Ilu0). The vector `x`
+//! holds the initial guess on input and the solution on output.
+
+use crate::{
+    error::{ensure, Result},
+    mtx::Mtx,
+    numerical_type::NumericalType,
+    prec::Ilu0,
+    transpose::Transpose,
+};
+use num_complex::Complex;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+pub use scalar::*;
+mod scalar {
+    use super::*;
+
+    /// A numerical type supporting the arithmetic required by the solvers.
+    pub trait Scalar:
+        NumericalType
+        + PartialEq
+        + Add<Output = Self>
+        + Sub<Output = Self>
+        + Mul<Output = Self>
+        + Div<Output = Self>
+        + Neg<Output = Self>
+    {
+        fn one() -> Self;
+
+        fn from_f64(value: f64) -> Self;
+
+        /// The squared absolute value.
+        fn norm_sqr(&self) -> f64;
+
+        fn abs(&self) -> f64 {
+            self.norm_sqr().sqrt()
+        }
+    }
+
+    macro_rules! impl_real {
+        ($ty:ty) => {
+            impl Scalar for $ty {
+                fn one() -> Self {
+                    1.0
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value as $ty
+                }
+
+                fn norm_sqr(&self) -> f64 {
+                    let value = *self as f64;
+                    value * value
+                }
+            }
+        };
+    }
+
+    macro_rules! impl_complex {
+        ($ty:ty) => {
+            impl Scalar for Complex<$ty> {
+                fn one() -> Self {
+                    Complex::new(1.0, 0.0)
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    Complex::new(value as $ty, 0.0)
+                }
+
+                fn norm_sqr(&self) -> f64 {
+                    Complex::norm_sqr(self) as f64
+                }
+            }
+        };
+    }
+
+    impl_real!(f32);
+    impl_real!(f64);
+    impl_complex!(f32);
+    impl_complex!(f64);
+}
+
+pub use operator::*;
+mod operator {
+    use super::*;
+
+    /// A square linear operator `y = Ax`.
+    pub trait Operator<T> {
+        /// The number of rows and columns of the operator.
+        fn dim(&self) -> Result<usize>;
+
+        /// Compute `output = A * input`.
+        fn apply(&self, input: &[T], output: &mut [T]) -> Result<()>;
+    }
+
+    /// An approximate inverse `M⁻¹` of an operator.
+    pub trait Preconditioner<T> {
+        /// Compute `output = M⁻¹ * input`.
+        fn apply(&self, input: &[T], output: &mut [T]) -> Result<()>;
+    }
+
+    impl<T> Operator<T> for Mtx<T>
+    where
+        T: NumericalType,
+    {
+        fn dim(&self) -> Result<usize> {
+            let nr = self.nrows()?;
+            let nc = self.ncols()?;
+            ensure!(
+                nr == nc,
+                "the solvers require a square matrix, but the matrix is {}x{}",
+                nr,
+                nc
+            );
+            Ok(nr)
+        }
+
+        fn apply(&self, input: &[T], output: &mut [T]) -> Result<()> {
+            self.spmv(Transpose::None, None, input, T::zero(), output)
+        }
+    }
+
+    impl<T> Preconditioner<T> for Ilu0<T>
+    where
+        T: NumericalType,
+    {
+        fn apply(&self, input: &[T], output: &mut [T]) -> Result<()> {
+            Ilu0::apply(self, input, output)
+        }
+    }
+}
+
+pub use options::*;
+mod options {
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SolveOptions {
+        /// Stop when `‖b - Ax‖ <= rel_tol * ‖b‖`.
+        pub rel_tol: f64,
+        /// Stop when `‖b - Ax‖ <= abs_tol`.
+        pub abs_tol: f64,
+        /// The maximum number of iterations.
+        pub max_iters: usize,
+        /// The number of iterations between GMRES restarts.
+        pub restart: usize,
+    }
+
+    impl Default for SolveOptions {
+        fn default() -> Self {
+            Self {
+                rel_tol: 1e-8,
+                abs_tol: 0.0,
+                max_iters: 1000,
+                restart: 30,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Termination {
+        /// The residual norm reached the relative tolerance.
+        RelativeTolerance,
+        /// The residual norm reached the absolute tolerance.
+        AbsoluteTolerance,
+        /// The iteration limit was reached before convergence.
+        MaxIterations,
+        /// The method broke down due to a vanishing denominator.
+        Breakdown,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SolveReport {
+        /// The reason the solver stopped.
+        pub termination: Termination,
+        /// The number of iterations performed.
+        pub iterations: usize,
+        /// The residual norms, starting from the initial guess.
+        pub residual_history: Vec<f64>,
+    }
+
+    impl SolveReport {
+        pub fn converged(&self) -> bool {
+            matches!(
+                self.termination,
+                Termination::RelativeTolerance | Termination::AbsoluteTolerance
+            )
+        }
+
+        /// The last recorded residual norm.
+        pub fn residual(&self) -> f64 {
+            self.residual_history.last().copied().unwrap_or(0.0)
+        }
+    }
+
+    /// The stopping test shared by the solvers.
+    pub(super) struct Criterion {
+        rel_bound: f64,
+        abs_bound: f64,
+    }
+
+    impl Criterion {
+        pub fn new(options: &SolveOptions, rhs_norm: f64) -> Self {
+            Self {
+                rel_bound: options.rel_tol * rhs_norm,
+                abs_bound: options.abs_tol,
+            }
+        }
+
+        pub fn check(&self, residual: f64) -> Option<Termination> {
+            if residual <= self.abs_bound {
+                Some(Termination::AbsoluteTolerance)
+            } else if residual <= self.rel_bound {
+                Some(Termination::RelativeTolerance)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+mod blas {
+    use super::*;
+
+    /// The inner product `xᴴy`.
+    pub fn dot<T: Scalar>(x: &[T], y: &[T]) -> T {
+        x.iter()
+            .zip(y)
+            .fold(T::zero(), |sum, (x, y)| sum + x.conj() * *y)
+    }
+
+    pub fn norm<T: Scalar>(x: &[T]) -> f64 {
+        x.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt()
+    }
+
+    /// Compute `y += alpha * x`.
+    pub fn axpy<T: Scalar>(alpha: T, x: &[T], y: &mut [T]) {
+        y.iter_mut().zip(x).for_each(|(y, x)| *y = *y + alpha * *x);
+    }
+
+    /// Compute `output = rhs - A * x`.
+    pub fn residual<T, A>(op: &A, rhs: &[T], x: &[T], output: &mut [T]) -> Result<()>
+    where
+        T: Scalar,
+        A: Operator<T> + ?Sized,
+    {
+        op.apply(x, output)?;
+        output.iter_mut().zip(rhs).for_each(|(r, b)| *r = *b - *r);
+        Ok(())
+    }
+
+    /// Compute `output = M⁻¹ * input`, or copy if there is no preconditioner.
+    pub fn precondition<T>(
+        precond: Option<&dyn Preconditioner<T>>,
+        input: &[T],
+        output: &mut [T],
+    ) -> Result<()>
+    where
+        T: Scalar,
+    {
+        match precond {
+            Some(precond) => precond.apply(input, output),
+            None => {
+                output.copy_from_slice(input);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn check_dims<T, A>(op: &A, rhs: &[T], x: &[T]) -> Result<usize>
+where
+    A: Operator<T> + ?Sized,
+{
+    let n = op.dim()?;
+    ensure!(
+        rhs.len() == n,
+        "the rhs has length {}, but the operator dimension is {}",
+        rhs.len(),
+        n
+    );
+    ensure!(
+        x.len() == n,
+        "the solution has length {}, but the operator dimension is {}",
+        x.len(),
+        n
+    );
+    Ok(n)
+}
+
+pub use cg::*;
+mod cg {
+    use super::{blas::*, *};
+
+    /// Solve a symmetric (or hermitian) positive definite system by the
+    /// preconditioned Conjugate Gradient method.
+    pub fn cg<T, A>(
+        op: &A,
+        precond: Option<&dyn Preconditioner<T>>,
+        rhs: &[T],
+        x: &mut [T],
+        options: &SolveOptions,
+    ) -> Result<SolveReport>
+    where
+        T: Scalar,
+        A: Operator<T> + ?Sized,
+    {
+        let n = check_dims(op, rhs, x)?;
+        let criterion = Criterion::new(options, norm(rhs));
+
+        let mut r = vec![T::zero(); n];
+        residual(op, rhs, x, &mut r)?;
+        let mut history = vec![norm(&r)];
+
+        let mut z = vec![T::zero(); n];
+        precondition(precond, &r, &mut z)?;
+        let mut p = z.clone();
+        let mut ap = vec![T::zero(); n];
+        let mut rz = dot(&r, &z);
+
+        let mut iterations = 0;
+        let termination = loop {
+            if let Some(termination) = criterion.check(*history.last().unwrap()) {
+                break termination;
+            }
+            if iterations >= options.max_iters {
+                break Termination::MaxIterations;
+            }
+
+            op.apply(&p, &mut ap)?;
+            let pap = dot(&p, &ap);
+            if pap == T::zero() {
+                break Termination::Breakdown;
+            }
+
+            let alpha = rz / pap;
+            axpy(alpha, &p, x);
+            axpy(-alpha, &ap, &mut r);
+            iterations += 1;
+            history.push(norm(&r));
+
+            precondition(precond, &r, &mut z)?;
+            let rz_new = dot(&r, &z);
+            if rz == T::zero() {
+                break Termination::Breakdown;
+            }
+            let beta = rz_new / rz;
+            rz = rz_new;
+            p.iter_mut().zip(&z).for_each(|(p, z)| *p = *z + beta * *p);
+        };
+
+        Ok(SolveReport {
+            termination,
+            iterations,
+            residual_history: history,
+        })
+    }
+}
+
+pub use bicgstab::*;
+mod bicgstab {
+    use super::{blas::*, *};
+
+    /// Solve a general system by the right-preconditioned BiCGSTAB method.
+    pub fn bicgstab<T, A>(
+        op: &A,
+        precond: Option<&dyn Preconditioner<T>>,
+        rhs: &[T],
+        x: &mut [T],
+        options: &SolveOptions,
+    ) -> Result<SolveReport>
+    where
+        T: Scalar,
+        A: Operator<T> + ?Sized,
+    {
+        let n = check_dims(op, rhs, x)?;
+        let criterion = Criterion::new(options, norm(rhs));
+
+        let mut r = vec![T::zero(); n];
+        residual(op, rhs, x, &mut r)?;
+        let mut history = vec![norm(&r)];
+
+        let r_hat = r.clone();
+        let mut p = vec![T::zero(); n];
+        let mut v = vec![T::zero(); n];
+        let mut p_hat = vec![T::zero(); n];
+        let mut s = vec![T::zero(); n];
+        let mut s_hat = vec![T::zero(); n];
+        let mut t = vec![T::zero(); n];
+        let mut rho = T::one();
+        let mut alpha = T::one();
+        let mut omega = T::one();
+
+        let mut iterations = 0;
+        let termination = loop {
+            if let Some(termination) = criterion.check(*history.last().unwrap()) {
+                break termination;
+            }
+            if iterations >= options.max_iters {
+                break Termination::MaxIterations;
+            }
+
+            let rho_new = dot(&r_hat, &r);
+            if rho_new == T::zero() || omega == T::zero() {
+                break Termination::Breakdown;
+            }
+            let beta = (rho_new / rho) * (alpha / omega);
+            rho = rho_new;
+
+            p.iter_mut()
+                .zip(&r)
+                .zip(&v)
+                .for_each(|((p, r), v)| *p = *r + beta * (*p - omega * *v));
+            precondition(precond, &p, &mut p_hat)?;
+            op.apply(&p_hat, &mut v)?;
+
+            let r_hat_v = dot(&r_hat, &v);
+            if r_hat_v == T::zero() {
+                break Termination::Breakdown;
+            }
+            alpha = rho / r_hat_v;
+
+            s.iter_mut()
+                .zip(&r)
+                .zip(&v)
+                .for_each(|((s, r), v)| *s = *r - alpha * *v);
+            iterations += 1;
+
+            let s_norm = norm(&s);
+            if criterion.check(s_norm).is_some() {
+                axpy(alpha, &p_hat, x);
+                r.copy_from_slice(&s);
+                history.push(s_norm);
+                continue;
+            }
+
+            precondition(precond, &s, &mut s_hat)?;
+            op.apply(&s_hat, &mut t)?;
+            let tt = dot(&t, &t);
+            if tt == T::zero() {
+                break Termination::Breakdown;
+            }
+            omega = dot(&t, &s) / tt;
+
+            axpy(alpha, &p_hat, x);
+            axpy(omega, &s_hat, x);
+            r.iter_mut()
+                .zip(&s)
+                .zip(&t)
+                .for_each(|((r, s), t)| *r = *s - omega * *t);
+            history.push(norm(&r));
+        };
+
+        Ok(SolveReport {
+            termination,
+            iterations,
+            residual_history: history,
+        })
+    }
+}
+
+pub use gmres::*;
+mod gmres {
+    use super::{blas::*, *};
+
+    /// Solve a general system by the right-preconditioned GMRES method,
+    /// restarted every [SolveOptions::restart] iterations.
+    pub fn gmres<T, A>(
+        op: &A,
+        precond: Option<&dyn Preconditioner<T>>,
+        rhs: &[T],
+        x: &mut [T],
+        options: &SolveOptions,
+    ) -> Result<SolveReport>
+    where
+        T: Scalar,
+        A: Operator<T> + ?Sized,
+    {
+        let n = check_dims(op, rhs, x)?;
+        ensure!(options.restart >= 1, "the GMRES restart must be positive");
+        let criterion = Criterion::new(options, norm(rhs));
+        let m = options.restart;
+
+        let mut r = vec![T::zero(); n];
+        let mut w = vec![T::zero(); n];
+        let mut z = vec![T::zero(); n];
+        let mut basis: Vec<Vec<T>> = vec![vec![T::zero(); n]; m + 1];
+        // The Hessenberg matrix is stored by columns, each of length m + 1.
+        let mut h: Vec<Vec<T>> = vec![vec![T::zero(); m + 1]; m];
+        let mut rotations: Vec<(T, T)> = vec![(T::zero(), T::zero()); m];
+        let mut g = vec![T::zero(); m + 1];
+
+        residual(op, rhs, x, &mut r)?;
+        let mut history = vec![norm(&r)];
+        let mut iterations = 0;
+
+        let termination = 'outer: loop {
+            let beta = *history.last().unwrap();
+            if let Some(termination) = criterion.check(beta) {
+                break termination;
+            }
+            if iterations >= options.max_iters {
+                break Termination::MaxIterations;
+            }
+
+            let inv_beta = T::from_f64(1.0 / beta);
+            basis[0]
+                .iter_mut()
+                .zip(&r)
+                .for_each(|(v, r)| *v = *r * inv_beta);
+            g.iter_mut().for_each(|g| *g = T::zero());
+            g[0] = T::from_f64(beta);
+
+            let mut k = 0;
+            let mut stop = None;
+
+            while k < m && iterations < options.max_iters {
+                let j = k;
+                precondition(precond, &basis[j], &mut z)?;
+                op.apply(&z, &mut w)?;
+
+                // Modified Gram-Schmidt orthogonalization.
+                for i in 0..=j {
+                    let hij = dot(&basis[i], &w);
+                    h[j][i] = hij;
+                    axpy(-hij, &basis[i], &mut w);
+                }
+                let w_norm = norm(&w);
+                h[j][j + 1] = T::from_f64(w_norm);
+                if w_norm != 0.0 {
+                    let inv = T::from_f64(1.0 / w_norm);
+                    basis[j + 1]
+                        .iter_mut()
+                        .zip(&w)
+                        .for_each(|(v, w)| *v = *w * inv);
+                }
+
+                for (i, &(c, s)) in rotations.iter().enumerate().take(j) {
+                    let (a, b) = (h[j][i], h[j][i + 1]);
+                    h[j][i] = c * a + s * b;
+                    h[j][i + 1] = -s.conj() * a + c * b;
+                }
+
+                let (c, s, diag) = givens(h[j][j], h[j][j + 1]);
+                rotations[j] = (c, s);
+                h[j][j] = diag;
+                h[j][j + 1] = T::zero();
+                g[j + 1] = -s.conj() * g[j];
+                g[j] = c * g[j];
+
+                k += 1;
+                iterations += 1;
+                let res = g[j + 1].abs();
+                history.push(res);
+
+                if let Some(termination) = criterion.check(res) {
+                    stop = Some(termination);
+                    break;
+                }
+                if w_norm == 0.0 {
+                    // The Krylov subspace is invariant but the residual is
+                    // not small, which can only happen with a singular operator.
+                    stop = Some(Termination::Breakdown);
+                    break;
+                }
+            }
+
+            // Solve the upper triangular system H y = g by back substitution.
+            let mut y = g[..k].to_vec();
+            for i in (0..k).rev() {
+                let sum = ((i + 1)..k).fold(y[i], |sum, l| sum - h[l][i] * y[l]);
+                if h[i][i] == T::zero() {
+                    break 'outer Termination::Breakdown;
+                }
+                y[i] = sum / h[i][i];
+            }
+
+            // Update x += M⁻¹ (V y).
+            w.iter_mut().for_each(|w| *w = T::zero());
+            for (v, &yi) in basis.iter().zip(&y) {
+                axpy(yi, v, &mut w);
+            }
+            precondition(precond, &w, &mut z)?;
+            axpy(T::one(), &z, x);
+
+            // Replace the estimated residual with the true one.
+            residual(op, rhs, x, &mut r)?;
+            *history.last_mut().unwrap() = norm(&r);
+
+            if let Some(termination) = stop {
+                match termination {
+                    Termination::Breakdown => break termination,
+                    _ => {
+                        // Confirm the convergence with the true residual.
+                        if let Some(termination) = criterion.check(*history.last().unwrap()) {
+                            break termination;
+                        }
+                    }
+                }
+            }
+        };
+
+        Ok(SolveReport {
+            termination,
+            iterations,
+            residual_history: history,
+        })
+    }
+
+    /// Compute the rotation `(c, s)` such that
+    /// `[c, s; -conj(s), c] [a; b] = [r; 0]`, and return `(c, s, r)`.
+    fn givens<T: Scalar>(a: T, b: T) -> (T, T, T) {
+        let a_abs = a.abs();
+        if a_abs == 0.0 {
+            return (T::zero(), T::one(), b);
+        }
+
+        let norm = (a.norm_sqr() + b.norm_sqr()).sqrt();
+        let phase = a / T::from_f64(a_abs);
+        let c = T::from_f64(a_abs / norm);
+        let s = phase * b.conj() / T::from_f64(norm);
+        let r = phase * T::from_f64(norm);
+        (c, s, r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blas::*, *};
+
+    /// A dense row major matrix, used to test the solvers without librsb.
+    struct Dense<T> {
+        dim: usize,
+        values: Vec<T>,
+    }
+
+    impl<T: Scalar> Dense<T> {
+        fn new(rows: &[&[T]]) -> Self {
+            Self {
+                dim: rows.len(),
+                values: rows.iter().flat_map(|row| row.iter().copied()).collect(),
+            }
+        }
+    }
+
+    impl<T: Scalar> Operator<T> for Dense<T> {
+        fn dim(&self) -> Result<usize> {
+            Ok(self.dim)
+        }
+
+        fn apply(&self, input: &[T], output: &mut [T]) -> Result<()> {
+            for (row, output) in self.values.chunks(self.dim).zip(output.iter_mut()) {
+                *output = row
+                    .iter()
+                    .zip(input)
+                    .fold(T::zero(), |sum, (a, x)| sum + *a * *x);
+            }
+            Ok(())
+        }
+    }
+
+    /// The Jacobi preconditioner `M = diag(A)`.
+    struct Jacobi<T>(Vec<T>);
+
+    impl<T: Scalar> Preconditioner<T> for Jacobi<T> {
+        fn apply(&self, input: &[T], output: &mut [T]) -> Result<()> {
+            output
+                .iter_mut()
+                .zip(input)
+                .zip(&self.0)
+                .for_each(|((y, x), d)| *y = *x / *d);
+            Ok(())
+        }
+    }
+
+    fn real_spd() -> Dense<f64> {
+        Dense::new(&[
+            &[4.0, 1.0, 0.0, 0.0],
+            &[1.0, 4.0, 1.0, 0.0],
+            &[0.0, 1.0, 4.0, 1.0],
+            &[0.0, 0.0, 1.0, 3.0],
+        ])
+    }
+
+    fn real_nonsymmetric() -> Dense<f64> {
+        Dense::new(&[
+            &[4.0, -1.0, 0.0, 2.0],
+            &[2.0, 5.0, -1.0, 0.0],
+            &[0.0, 3.0, 6.0, -2.0],
+            &[1.0, 0.0, -1.0, 3.0],
+        ])
+    }
+
+    fn complex_hpd() -> Dense<Complex<f64>> {
+        let c = Complex::new;
+        Dense::new(&[
+            &[c(4.0, 0.0), c(1.0, 1.0), c(0.0, 0.0)],
+            &[c(1.0, -1.0), c(5.0, 0.0), c(0.0, 2.0)],
+            &[c(0.0, 0.0), c(0.0, -2.0), c(6.0, 0.0)],
+        ])
+    }
+
+    fn complex_nonsymmetric() -> Dense<Complex<f64>> {
+        let c = Complex::new;
+        Dense::new(&[
+            &[c(4.0, 1.0), c(-1.0, 0.0), c(0.0, 2.0)],
+            &[c(2.0, 0.0), c(5.0, -1.0), c(1.0, 0.0)],
+            &[c(0.0, 1.0), c(3.0, 0.0), c(6.0, 0.0)],
+        ])
+    }
+
+    fn rhs<T: Scalar>(op: &Dense<T>) -> Vec<T> {
+        (1..=op.dim).map(|i| T::from_f64(i as f64)).collect()
+    }
+
+    fn diagonal<T: Scalar>(op: &Dense<T>) -> Jacobi<T> {
+        Jacobi((0..op.dim).map(|i| op.values[i * op.dim + i]).collect())
+    }
+
+    /// Check the report and the true residual of a solution.
+    fn check_solution<T: Scalar>(
+        op: &Dense<T>,
+        b: &[T],
+        x: &[T],
+        report: &SolveReport,
+        options: &SolveOptions,
+    ) {
+        assert!(report.converged(), "{:?}", report);
+        assert_eq!(report.termination, Termination::RelativeTolerance);
+
+        let history = &report.residual_history;
+        assert_eq!(history.len(), report.iterations + 1);
+        assert_eq!(history[0], norm(b));
+        assert!(report.residual() <= options.rel_tol * norm(b));
+        assert!(history.iter().all(|res| res.is_finite()));
+
+        let mut r = vec![T::zero(); b.len()];
+        residual(op, b, x, &mut r).unwrap();
+        assert!(norm(&r) <= 10.0 * options.rel_tol * norm(b));
+    }
+
+    type Solver<T> = fn(
+        &Dense<T>,
+        Option<&dyn Preconditioner<T>>,
+        &[T],
+        &mut [T],
+        &SolveOptions,
+    ) -> Result<SolveReport>;
+
+    fn check_solver<T: Scalar>(solver: Solver<T>, op: &Dense<T>) {
+        let options = SolveOptions::default();
+        let b = rhs(op);
+        let jacobi = diagonal(op);
+
+        for precond in [None, Some(&jacobi as &dyn Preconditioner<T>)] {
+            let mut x = vec![T::zero(); op.dim];
+            let report = solver(op, precond, &b, &mut x, &options).unwrap();
+            check_solution(op, &b, &x, &report, &options);
+        }
+    }
+
+    #[test]
+    fn cg_real_spd() {
+        check_solver(cg, &real_spd());
+    }
+
+    #[test]
+    fn cg_complex_hpd() {
+        check_solver(cg, &complex_hpd());
+    }
+
+    #[test]
+    fn cg_finite_termination() {
+        let op = real_spd();
+        let b = rhs(&op);
+        let mut x = vec![0.0; op.dim];
+        let report = cg(&op, None, &b, &mut x, &SolveOptions::default()).unwrap();
+
+        // CG terminates within n steps in exact arithmetic.
+        assert!(report.iterations <= op.dim + 1);
+    }
+
+    #[test]
+    fn bicgstab_real_nonsymmetric() {
+        check_solver(bicgstab, &real_nonsymmetric());
+    }
+
+    #[test]
+    fn bicgstab_complex_nonsymmetric() {
+        check_solver(bicgstab, &complex_nonsymmetric());
+    }
+
+    #[test]
+    fn gmres_real_nonsymmetric() {
+        check_solver(gmres, &real_nonsymmetric());
+    }
+
+    #[test]
+    fn gmres_complex_nonsymmetric() {
+        check_solver(gmres, &complex_nonsymmetric());
+    }
+
+    #[test]
+    fn gmres_restarted() {
+        let op = real_nonsymmetric();
+        let b = rhs(&op);
+        let mut x = vec![0.0; op.dim];
+        let options = SolveOptions {
+            restart: 2,
+            ..Default::default()
+        };
+        let report = gmres(&op, None, &b, &mut x, &options).unwrap();
+        check_solution(&op, &b, &x, &report, &options);
+        assert!(report.iterations > 2);
+    }
+
+    #[test]
+    fn initial_solution_stops_immediately() {
+        let op = real_spd();
+        let b = vec![0.0; op.dim];
+        let mut x = vec![0.0; op.dim];
+        let report = cg(&op, None, &b, &mut x, &SolveOptions::default()).unwrap();
+
+        assert_eq!(report.termination, Termination::AbsoluteTolerance);
+        assert_eq!(report.iterations, 0);
+        assert_eq!(report.residual_history, [0.0]);
+    }
+
+    #[test]
+    fn iteration_limit() {
+        let op = real_nonsymmetric();
+        let b = rhs(&op);
+        let mut x = vec![0.0; op.dim];
+        let options = SolveOptions {
+            max_iters: 1,
+            rel_tol: 1e-14,
+            ..Default::default()
+        };
+        let report = bicgstab(&op, None, &b, &mut x, &options).unwrap();
+
+        assert_eq!(report.termination, Termination::MaxIterations);
+        assert!(!report.converged());
+        assert_eq!(report.iterations, 1);
+        assert_eq!(report.residual_history.len(), 2);
+    }
+
+    #[test]
+    fn dimension_mismatch() {
+        let op = real_spd();
+        let b = vec![1.0; op.dim + 1];
+        let mut x = vec![0.0; op.dim];
+        assert!(cg(&op, None, &b, &mut x, &SolveOptions::default()).is_err());
+    }
+}
@@ -27,7 +27,7 @@ pub enum Limit {
 }
 
 impl Limit {
-    fn to_value(&self) -> sys::rsb_time_t {
+    pub(crate) fn to_value(&self) -> sys::rsb_time_t {
         match *self {
             Self::Default => 0.0,
             Self::Time(dur) => dur.as_secs_f64(),
@@ -41,3 +41,21 @@ impl Default for Limit {
         Self::Default
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TuningMode {
+    /// Re-block the matrix in place and find the best thread count.
+    InPlace,
+    /// Find the best thread count only, leaving the matrix untouched.
+    ThreadCountOnly,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningReport {
+    /// The speedup factor of the tuned operation over the untuned one.
+    pub speedup: f64,
+    /// The optimal thread count.
+    pub num_threads: usize,
+    /// The wall time spent on tuning.
+    pub elapsed: Duration,
+}
@@ -1,4 +1,30 @@
-use crate::common::*;
+use crate::{common::*, flag::Flags};
 
-pub(crate) const DEFAULT_STORAGE_FLAGS: sys::rsb_flags_t =
-    (sys::RSB_FLAG_WANT_BCSS_STORAGE | sys::RSB_FLAG_WANT_COO_STORAGE) as sys::rsb_flags_t;
+/// The kind of leaves librsb may use to store a matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StorageFormat {
+    /// Let librsb decide.
+    #[default]
+    Auto,
+    /// Both coordinate and compressed sparse row leaves.
+    Default,
+    /// Coordinate leaves only.
+    Coo,
+    /// Compressed sparse row leaves only.
+    Csr,
+    /// The recommended RSB layout with quad partitioning and halfword indices.
+    Rsb,
+}
+
+impl StorageFormat {
+    pub fn code(&self) -> sys::rsb_flags_t {
+        let flags = match self {
+            Self::Auto => Flags::empty(),
+            Self::Default => Flags::DEFAULT_STORAGE,
+            Self::Coo => Flags::DEFAULT_COO_MATRIX,
+            Self::Csr => Flags::DEFAULT_CSR_MATRIX,
+            Self::Rsb => Flags::DEFAULT_RSB_MATRIX,
+        };
+        flags.code()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::common::*;
+use crate::{common::*, flag::Flags};
 
 pub trait SymmetryType {
     const SYMMETRY_CODE: sys::rsb_flags_t;
@@ -61,6 +61,35 @@ pub enum Symmetry {
 }
 
 impl Symmetry {
+    /// Decode the symmetry from matrix flags reported by librsb.
+    pub fn from_flags(flags: Flags) -> Self {
+        let has = |flag: Flags| flags.contains(flag);
+
+        if has(Flags::HERMITIAN) {
+            if has(Flags::UPPER) {
+                Self::UpperHermitian
+            } else {
+                Self::LowerHermitian
+            }
+        } else if has(Flags::SYMMETRIC) {
+            if has(Flags::UPPER) {
+                Self::UpperSymmetric
+            } else if has(Flags::LOWER) {
+                Self::LowerSymmetric
+            } else {
+                Self::Symmetric
+            }
+        } else if has(Flags::TRIANGULAR) {
+            if has(Flags::UPPER) {
+                Self::UpperTriangular
+            } else {
+                Self::LowerTriangular
+            }
+        } else {
+            Self::General
+        }
+    }
+
     pub fn code(&self) -> sys::rsb_flags_t {
         let flag = match self {
             Symmetry::General => 0x00,